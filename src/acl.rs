@@ -0,0 +1,676 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::errors::{ErrorKind, Result};
+use crate::request::{delete, get, post, put};
+use crate::{Client, QueryMeta, QueryOptions, Redacted, WriteMeta, WriteOptions};
+
+/// A reference to a policy or role, by `ID` or by `Name`.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct ACLLink {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ID: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Name: Option<String>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct ACLServiceIdentity {
+    pub ServiceName: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Datacenters: Option<Vec<String>>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct ACLToken {
+    /// Public identifier of the token, generated by Consul when left empty.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub AccessorID: String,
+    /// The secret sent as `X-Consul-Token`, generated by Consul when left
+    /// empty.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub SecretID: String,
+    pub Description: String,
+    pub Policies: Option<Vec<ACLLink>>,
+    pub Roles: Option<Vec<ACLLink>>,
+    pub ServiceIdentities: Option<Vec<ACLServiceIdentity>>,
+    pub Local: bool,
+    /// Consul Enterprise namespace of the token, left to the request's when
+    /// empty.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing)]
+    pub CreateTime: Option<String>,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+impl fmt::Debug for ACLToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ACLToken")
+            .field("AccessorID", &self.AccessorID)
+            .field("SecretID", &(!self.SecretID.is_empty()).then_some(Redacted))
+            .field("Description", &self.Description)
+            .field("Policies", &self.Policies)
+            .field("Roles", &self.Roles)
+            .field("ServiceIdentities", &self.ServiceIdentities)
+            .field("Local", &self.Local)
+            .field("Namespace", &self.Namespace)
+            .field("CreateTime", &self.CreateTime)
+            .field("CreateIndex", &self.CreateIndex)
+            .field("ModifyIndex", &self.ModifyIndex)
+            .finish()
+    }
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct ACLPolicy {
+    /// Generated by Consul on creation.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ID: String,
+    pub Name: String,
+    pub Description: String,
+    /// The policy rules, in HCL or JSON.
+    pub Rules: String,
+    pub Datacenters: Option<Vec<String>>,
+    /// Consul Enterprise namespace of the policy, left to the request's when
+    /// empty.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+/// A named bundle of policies and service identities, which tokens link to
+/// through an `ACLLink` in their `Roles`.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct ACLRole {
+    /// Generated by Consul on creation.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ID: String,
+    pub Name: String,
+    pub Description: String,
+    pub Policies: Option<Vec<ACLLink>>,
+    pub ServiceIdentities: Option<Vec<ACLServiceIdentity>>,
+    /// Consul Enterprise namespace of the role, left to the request's when
+    /// empty.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+/// A trusted identity provider whose credentials `login` exchanges for
+/// tokens.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct ACLAuthMethod {
+    /// Identifies the method, it cannot be changed once created.
+    pub Name: String,
+    /// `kubernetes`, `jwt` or `oidc`.
+    pub Type: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub DisplayName: String,
+    pub Description: String,
+    /// The longest lifetime of the tokens issued through the method, as a Go
+    /// duration; tokens do not expire when empty.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub MaxTokenTTL: String,
+    /// Whether issued tokens are `local` to the datacenter, the default, or
+    /// `global`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub TokenLocality: String,
+    /// Settings of the method's `Type`, e.g. the `Host` and `CACert` of a
+    /// Kubernetes cluster or the `JWKSURL` to validate JWTs with.
+    /// https://www.consul.io/docs/security/acl/auth-methods
+    pub Config: HashMap<String, Value>,
+    /// Consul Enterprise namespace of the method, left to the request's when
+    /// empty.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+impl fmt::Debug for ACLAuthMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `Config` may hold client secrets or service account tokens
+        let config: BTreeMap<_, _> = self.Config.keys().map(|key| (key, Redacted)).collect();
+        f.debug_struct("ACLAuthMethod")
+            .field("Name", &self.Name)
+            .field("Type", &self.Type)
+            .field("DisplayName", &self.DisplayName)
+            .field("Description", &self.Description)
+            .field("MaxTokenTTL", &self.MaxTokenTTL)
+            .field("TokenLocality", &self.TokenLocality)
+            .field("Config", &config)
+            .field("Namespace", &self.Namespace)
+            .field("CreateIndex", &self.CreateIndex)
+            .field("ModifyIndex", &self.ModifyIndex)
+            .finish()
+    }
+}
+
+/// Maps the identities an auth method verifies onto roles, service or node
+/// identities of the tokens `login` issues.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct ACLBindingRule {
+    /// Generated by Consul on creation.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ID: String,
+    pub Description: String,
+    /// The `Name` of the auth method the rule applies to.
+    pub AuthMethod: String,
+    /// A filter expression over the verified claims, the rule applying to
+    /// every login when empty.
+    pub Selector: String,
+    /// `service`, `node`, `role` or `templated-policy`.
+    pub BindType: String,
+    /// The name bound, which may interpolate claims, e.g.
+    /// `${serviceaccount.name}`.
+    pub BindName: String,
+    /// Consul Enterprise namespace of the rule, left to the request's when
+    /// empty.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+#[derive(Serialize)]
+struct ACLLoginParams<'a> {
+    AuthMethod: &'a str,
+    BearerToken: &'a str,
+    Meta: HashMap<String, String>,
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[async_trait]
+pub trait ACL {
+    async fn bootstrap(&self) -> Result<ACLToken>;
+    async fn login(
+        &self,
+        auth_method: &str,
+        bearer_token: &str,
+        meta: HashMap<String, String>,
+    ) -> Result<ACLToken>;
+    async fn logout(&self, token: &str) -> Result<()>;
+    async fn token_create(
+        &self,
+        token: &ACLToken,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLToken, WriteMeta)>;
+    async fn token_read(
+        &self,
+        accessor_id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ACLToken, QueryMeta)>;
+    async fn token_update(
+        &self,
+        token: &ACLToken,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLToken, WriteMeta)>;
+    async fn token_delete(
+        &self,
+        accessor_id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)>;
+    async fn token_list(&self, q: Option<&QueryOptions>) -> Result<(Vec<ACLToken>, QueryMeta)>;
+    async fn policy_create(
+        &self,
+        policy: &ACLPolicy,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLPolicy, WriteMeta)>;
+    async fn policy_read(
+        &self,
+        id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ACLPolicy, QueryMeta)>;
+    async fn policy_update(
+        &self,
+        policy: &ACLPolicy,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLPolicy, WriteMeta)>;
+    async fn policy_delete(&self, id: &str, q: Option<&WriteOptions>) -> Result<(bool, WriteMeta)>;
+    async fn policy_list(&self, q: Option<&QueryOptions>) -> Result<(Vec<ACLPolicy>, QueryMeta)>;
+    async fn role_create(
+        &self,
+        role: &ACLRole,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLRole, WriteMeta)>;
+    async fn role_read(&self, id: &str, q: Option<&QueryOptions>) -> Result<(ACLRole, QueryMeta)>;
+    async fn role_read_by_name(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ACLRole, QueryMeta)>;
+    async fn role_update(
+        &self,
+        role: &ACLRole,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLRole, WriteMeta)>;
+    async fn role_delete(&self, id: &str, q: Option<&WriteOptions>) -> Result<(bool, WriteMeta)>;
+    async fn role_list(&self, q: Option<&QueryOptions>) -> Result<(Vec<ACLRole>, QueryMeta)>;
+    async fn auth_method_create(
+        &self,
+        method: &ACLAuthMethod,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLAuthMethod, WriteMeta)>;
+    async fn auth_method_read(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ACLAuthMethod, QueryMeta)>;
+    async fn auth_method_update(
+        &self,
+        method: &ACLAuthMethod,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLAuthMethod, WriteMeta)>;
+    async fn auth_method_delete(
+        &self,
+        name: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)>;
+    async fn auth_method_list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<ACLAuthMethod>, QueryMeta)>;
+    async fn binding_rule_create(
+        &self,
+        rule: &ACLBindingRule,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLBindingRule, WriteMeta)>;
+    async fn binding_rule_read(
+        &self,
+        id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ACLBindingRule, QueryMeta)>;
+    async fn binding_rule_update(
+        &self,
+        rule: &ACLBindingRule,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLBindingRule, WriteMeta)>;
+    async fn binding_rule_delete(
+        &self,
+        id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)>;
+    async fn binding_rule_list(
+        &self,
+        auth_method: Option<&str>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<ACLBindingRule>, QueryMeta)>;
+}
+
+#[async_trait]
+impl ACL for Client {
+    /// https://www.consul.io/api/acl/acl.html#bootstrap-acls
+    ///
+    /// Returns the initial management token. Once the cluster has been
+    /// bootstrapped this fails with `ErrorKind::AlreadyBootstrapped`, which
+    /// provisioning scripts can treat as success.
+    async fn bootstrap(&self) -> Result<ACLToken> {
+        let result = put(
+            "/v1/acl/bootstrap",
+            None as Option<&()>,
+            &self.config,
+            Vec::new(),
+            None,
+        )
+        .await;
+        match result {
+            Ok((token, _)) => Ok(token),
+            Err(e) => match e.kind() {
+                ErrorKind::Consul(403, body)
+                    if body.contains("ACL bootstrap no longer allowed") =>
+                {
+                    Err(e.chain_err(|| ErrorKind::AlreadyBootstrapped))
+                }
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// https://www.consul.io/api/acl/acl.html#login-to-auth-method
+    ///
+    /// Exchanges `bearer_token` (e.g. a Kubernetes service account JWT) for a
+    /// Consul token. The returned token includes its `SecretID`, ready to be
+    /// used for later requests.
+    async fn login(
+        &self,
+        auth_method: &str,
+        bearer_token: &str,
+        meta: HashMap<String, String>,
+    ) -> Result<ACLToken> {
+        let params = ACLLoginParams {
+            AuthMethod: auth_method,
+            BearerToken: bearer_token,
+            Meta: meta,
+        };
+        post(
+            "/v1/acl/login",
+            Some(&params),
+            &self.config,
+            Vec::new(),
+            None,
+        )
+        .await
+        .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/acl/acl.html#logout-from-auth-method
+    ///
+    /// Destroys `token`, which must have been issued by `login`.
+    async fn logout(&self, token: &str) -> Result<()> {
+        let options = WriteOptions {
+            token: Some(token.to_owned()),
+            ..Default::default()
+        };
+        post(
+            "/v1/acl/logout",
+            None as Option<&()>,
+            &self.config,
+            Vec::new(),
+            Some(&options),
+        )
+        .await
+        .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/acl/tokens.html#create-a-token
+    async fn token_create(
+        &self,
+        token: &ACLToken,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLToken, WriteMeta)> {
+        put("/v1/acl/token", Some(token), &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/tokens.html#read-a-token
+    async fn token_read(
+        &self,
+        accessor_id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ACLToken, QueryMeta)> {
+        let path = format!("/v1/acl/token/{}", accessor_id);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/tokens.html#update-a-token
+    ///
+    /// The token to update is identified by its `AccessorID`.
+    async fn token_update(
+        &self,
+        token: &ACLToken,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLToken, WriteMeta)> {
+        let path = format!("/v1/acl/token/{}", token.AccessorID);
+        put(&path, Some(token), &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/tokens.html#delete-a-token
+    async fn token_delete(
+        &self,
+        accessor_id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)> {
+        let path = format!("/v1/acl/token/{}", accessor_id);
+        delete(&path, None as Option<&()>, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/tokens.html#list-tokens
+    async fn token_list(&self, q: Option<&QueryOptions>) -> Result<(Vec<ACLToken>, QueryMeta)> {
+        get("/v1/acl/tokens", &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/policies.html#create-a-policy
+    ///
+    /// The returned policy carries the generated `ID`, which tokens and roles
+    /// reference through an `ACLLink`.
+    async fn policy_create(
+        &self,
+        policy: &ACLPolicy,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLPolicy, WriteMeta)> {
+        put("/v1/acl/policy", Some(policy), &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/policies.html#read-a-policy
+    async fn policy_read(
+        &self,
+        id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ACLPolicy, QueryMeta)> {
+        let path = format!("/v1/acl/policy/{}", id);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/policies.html#update-a-policy
+    ///
+    /// The policy to update is identified by its `ID`.
+    async fn policy_update(
+        &self,
+        policy: &ACLPolicy,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLPolicy, WriteMeta)> {
+        let path = format!("/v1/acl/policy/{}", policy.ID);
+        put(&path, Some(policy), &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/policies.html#delete-a-policy
+    async fn policy_delete(&self, id: &str, q: Option<&WriteOptions>) -> Result<(bool, WriteMeta)> {
+        let path = format!("/v1/acl/policy/{}", id);
+        delete(&path, None as Option<&()>, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/policies.html#list-policies
+    ///
+    /// Listed policies do not include their `Rules`.
+    async fn policy_list(&self, q: Option<&QueryOptions>) -> Result<(Vec<ACLPolicy>, QueryMeta)> {
+        get("/v1/acl/policies", &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/roles.html#create-a-role
+    ///
+    /// The returned role carries the generated `ID`.
+    async fn role_create(
+        &self,
+        role: &ACLRole,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLRole, WriteMeta)> {
+        put("/v1/acl/role", Some(role), &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/roles.html#read-a-role
+    async fn role_read(&self, id: &str, q: Option<&QueryOptions>) -> Result<(ACLRole, QueryMeta)> {
+        let path = format!("/v1/acl/role/{}", id);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/roles.html#read-a-role-by-name
+    async fn role_read_by_name(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ACLRole, QueryMeta)> {
+        let path = format!("/v1/acl/role/name/{}", name);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/roles.html#update-a-role
+    ///
+    /// The role to update is identified by its `ID`.
+    async fn role_update(
+        &self,
+        role: &ACLRole,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLRole, WriteMeta)> {
+        let path = format!("/v1/acl/role/{}", role.ID);
+        put(&path, Some(role), &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/roles.html#delete-a-role
+    async fn role_delete(&self, id: &str, q: Option<&WriteOptions>) -> Result<(bool, WriteMeta)> {
+        let path = format!("/v1/acl/role/{}", id);
+        delete(&path, None as Option<&()>, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/roles.html#list-roles
+    async fn role_list(&self, q: Option<&QueryOptions>) -> Result<(Vec<ACLRole>, QueryMeta)> {
+        get("/v1/acl/roles", &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/auth-methods.html#create-an-auth-method
+    async fn auth_method_create(
+        &self,
+        method: &ACLAuthMethod,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLAuthMethod, WriteMeta)> {
+        put(
+            "/v1/acl/auth-method",
+            Some(method),
+            &self.config,
+            Vec::new(),
+            q,
+        )
+        .await
+    }
+
+    /// https://www.consul.io/api/acl/auth-methods.html#read-an-auth-method
+    async fn auth_method_read(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ACLAuthMethod, QueryMeta)> {
+        let path = format!("/v1/acl/auth-method/{}", name);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/auth-methods.html#update-an-auth-method
+    ///
+    /// The method to update is identified by its `Name`, its `Type` must
+    /// stay the same.
+    async fn auth_method_update(
+        &self,
+        method: &ACLAuthMethod,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLAuthMethod, WriteMeta)> {
+        let path = format!("/v1/acl/auth-method/{}", method.Name);
+        put(&path, Some(method), &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/auth-methods.html#delete-an-auth-method
+    ///
+    /// Also deletes the method's binding rules and the tokens issued through
+    /// it.
+    async fn auth_method_delete(
+        &self,
+        name: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)> {
+        let path = format!("/v1/acl/auth-method/{}", name);
+        delete(&path, None as Option<&()>, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/auth-methods.html#list-auth-methods
+    ///
+    /// Listed methods do not include their `Config`.
+    async fn auth_method_list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<ACLAuthMethod>, QueryMeta)> {
+        get("/v1/acl/auth-methods", &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/binding-rules.html#create-a-binding-rule
+    ///
+    /// The returned rule carries the generated `ID`.
+    async fn binding_rule_create(
+        &self,
+        rule: &ACLBindingRule,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLBindingRule, WriteMeta)> {
+        put(
+            "/v1/acl/binding-rule",
+            Some(rule),
+            &self.config,
+            Vec::new(),
+            q,
+        )
+        .await
+    }
+
+    /// https://www.consul.io/api/acl/binding-rules.html#read-a-binding-rule
+    async fn binding_rule_read(
+        &self,
+        id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ACLBindingRule, QueryMeta)> {
+        let path = format!("/v1/acl/binding-rule/{}", id);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/binding-rules.html#update-a-binding-rule
+    ///
+    /// The rule to update is identified by its `ID`, its `AuthMethod` must
+    /// stay the same.
+    async fn binding_rule_update(
+        &self,
+        rule: &ACLBindingRule,
+        q: Option<&WriteOptions>,
+    ) -> Result<(ACLBindingRule, WriteMeta)> {
+        let path = format!("/v1/acl/binding-rule/{}", rule.ID);
+        put(&path, Some(rule), &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/binding-rules.html#delete-a-binding-rule
+    async fn binding_rule_delete(
+        &self,
+        id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)> {
+        let path = format!("/v1/acl/binding-rule/{}", id);
+        delete(&path, None as Option<&()>, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/acl/binding-rules.html#list-binding-rules
+    ///
+    /// Lists the rules of `auth_method` only, when given.
+    async fn binding_rule_list(
+        &self,
+        auth_method: Option<&str>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<ACLBindingRule>, QueryMeta)> {
+        let mut params = Vec::new();
+        if let Some(auth_method) = auth_method {
+            params.push((String::from("authmethod"), auth_method.to_owned()));
+        }
+        get("/v1/acl/binding-rules", &self.config, params, q).await
+    }
+}
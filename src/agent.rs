@@ -1,10 +1,53 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use reqwest::{Response, StatusCode};
+use serde_json::Value;
 
-use crate::errors::Result;
-use crate::request::{get, put};
-use crate::Client;
+use crate::catalog::Weights;
+use crate::encoding::rfc3339;
+use crate::errors::{Error, Result, ResultExt};
+use crate::health::{Health, HealthCheck};
+use crate::request::{get, get_bytes, get_streaming, get_with_status, post, put};
+use crate::{Client, Config, QueryMeta, QueryOptions, Redacted};
+
+#[derive(Clone, Copy, Default, Eq, PartialEq, Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    #[default]
+    Passing,
+    Warning,
+    /// Consul's status for checks that have not reported yet.
+    Critical,
+    Maintenance,
+    /// Any status this crate does not know about yet. Only ever read from
+    /// Consul: serializing it fails, rather than sending a made-up status.
+    #[serde(other)]
+    Unknown,
+}
+
+impl serde::Serialize for CheckStatus {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let status = match self {
+            CheckStatus::Passing => "passing",
+            CheckStatus::Warning => "warning",
+            CheckStatus::Critical => "critical",
+            CheckStatus::Maintenance => "maintenance",
+            CheckStatus::Unknown => {
+                return Err(serde::ser::Error::custom(
+                    "an unknown check status cannot be sent to Consul",
+                ))
+            }
+        };
+        serializer.serialize_str(status)
+    }
+}
 
 #[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
@@ -12,7 +55,7 @@ pub struct AgentCheck {
     pub Node: String,
     pub CheckID: String,
     pub Name: String,
-    pub Status: String,
+    pub Status: CheckStatus,
     pub Notes: String,
     pub Output: String,
     pub ServiceID: String,
@@ -26,7 +69,7 @@ pub struct AgentMember {
     pub Addr: String,
     pub Port: u16,
     pub Tags: HashMap<String, String>,
-    pub pubStatus: usize,
+    pub Status: usize,
     pub ProtocolMin: u8,
     pub ProtocolMax: u8,
     pub ProtocolCur: u8,
@@ -35,80 +78,535 @@ pub struct AgentMember {
     pub DelegateCur: u8,
 }
 
-#[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct AgentService {
     pub ID: String,
     pub Service: String,
     pub Tags: Option<Vec<String>>,
+    pub Meta: Option<HashMap<String, String>>,
     pub Port: u16,
     pub Address: String,
+    /// How DNS answers balance between instances, by health.
+    pub Weights: Option<Weights>,
+    /// Empty for a typical service, otherwise e.g. `connect-proxy` or
+    /// `ingress-gateway`.
+    pub Kind: String,
+    /// Set by Consul Enterprise, see `Config::namespace`.
+    pub Namespace: String,
+    /// Set by Consul Enterprise, see `Config::partition`.
+    pub Partition: String,
     pub EnableTagOverride: bool,
     pub CreateIndex: u64,
     pub ModifyIndex: u64,
 }
 
-//I haven't implemetned https://www.consul.io/api/agent.html#read-configuration
-//I haven't implemetned https://www.consul.io/api/agent.html#stream-logs
+/// A local service instance along with its checks.
+#[derive(Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentServiceChecksInfo {
+    /// The worst status among the service's checks.
+    pub AggregatedStatus: CheckStatus,
+    pub Service: AgentService,
+    pub Checks: Vec<AgentCheck>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentServiceCheck {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CheckID: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Status: Option<CheckStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub HTTP: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TCP: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TTL: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Interval: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Timeout: Option<String>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentServiceRegistration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ID: Option<String>,
+    pub Name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Meta: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub EnableTagOverride: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Check: Option<AgentServiceCheck>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Checks: Option<Vec<AgentServiceCheck>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Connect: Option<AgentServiceConnect>,
+    /// Consul Enterprise namespace to register in, the client's default
+    /// (`Config::namespace`) when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Namespace: Option<String>,
+    /// Consul Enterprise admin partition to register in, the client's
+    /// default (`Config::partition`) when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Partition: Option<String>,
+}
+
+/// The Connect settings of a service registration.
+/// https://www.consul.io/api-docs/agent/service#connect-structure
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentServiceConnect {
+    /// Whether the service speaks Connect itself, without a sidecar proxy.
+    pub Native: bool,
+}
+
+/// What `Agent::register_service_if_changed` did.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Registered {
+    /// No service with this ID was registered.
+    Created,
+    /// The registered service already matched, nothing was sent.
+    Unchanged,
+    /// The registered service differed and was replaced.
+    Updated,
+}
+
+/// The outcome of joining one address in `Agent::join_many`.
+#[derive(Debug)]
+pub struct JoinResult {
+    pub address: String,
+    pub result: Result<()>,
+}
+
+impl AgentServiceRegistration {
+    /// Whether `service` is what registering `self` would produce. Unset
+    /// fields compare equal to the agent's defaults; checks are not part of
+    /// `AgentService` and so are not compared.
+    fn matches(&self, service: &AgentService) -> bool {
+        fn or_empty<T: Default + Clone>(value: &Option<T>) -> T {
+            value.clone().unwrap_or_default()
+        }
+        self.Name == service.Service
+            && or_empty(&self.Tags) == or_empty(&service.Tags)
+            && or_empty(&self.Address) == service.Address
+            && self.Port.unwrap_or(0) == service.Port
+            && or_empty(&self.Meta) == or_empty(&service.Meta)
+            && self.EnableTagOverride.unwrap_or(false) == service.EnableTagOverride
+    }
+}
+
+/// A check definition for `/v1/agent/check/register`. Exactly one of the
+/// check kinds (`HTTP`, `TCP`, `Args`, `GRPC`, `DockerContainerID` or `TTL`)
+/// should be set; unset fields are left out of the payload entirely.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentCheckRegistration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ID: Option<String>,
+    pub Name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ServiceID: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Status: Option<CheckStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Interval: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Timeout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DeregisterCriticalServiceAfter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub HTTP: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Header: Option<HashMap<String, Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TLSSkipVerify: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TCP: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Args: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub GRPC: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub GRPCUseTLS: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DockerContainerID: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Shell: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TTL: Option<String>,
+}
+
+/// A service's Connect leaf certificate, with its private key.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LeafCert {
+    pub SerialNumber: String,
+    pub CertPEM: String,
+    pub PrivateKeyPEM: String,
+    pub Service: String,
+    /// The SPIFFE ID the certificate is issued to.
+    pub ServiceURI: String,
+    /// RFC 3339 timestamps bounding the certificate's validity.
+    pub ValidAfter: String,
+    pub ValidBefore: String,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+}
+
+impl fmt::Debug for LeafCert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LeafCert")
+            .field("SerialNumber", &self.SerialNumber)
+            .field("CertPEM", &self.CertPEM)
+            .field(
+                "PrivateKeyPEM",
+                &(!self.PrivateKeyPEM.is_empty()).then_some(Redacted),
+            )
+            .field("Service", &self.Service)
+            .field("ServiceURI", &self.ServiceURI)
+            .field("ValidAfter", &self.ValidAfter)
+            .field("ValidBefore", &self.ValidBefore)
+            .field("CreateIndex", &self.CreateIndex)
+            .field("ModifyIndex", &self.ModifyIndex)
+            .finish()
+    }
+}
+
+impl LeafCert {
+    /// `ValidBefore` parsed, `None` if missing or malformed.
+    pub fn valid_before(&self) -> Option<SystemTime> {
+        rfc3339::parse(&self.ValidBefore)
+    }
+}
+
+/// An inbound Connect connection to authorize, as a proxy sees it.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AuthorizeRequest {
+    /// The name of the destination service.
+    pub Target: String,
+    /// The SPIFFE ID of the client's certificate.
+    pub ClientCertURI: String,
+    /// The serial number of the client's certificate.
+    pub ClientCertSerial: String,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AuthorizeResponse {
+    pub Authorized: bool,
+    /// Why the connection was or was not authorized, to log denials with.
+    pub Reason: String,
+}
+
+/// The agent tokens `Agent::update_token` can replace.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TokenKind {
+    /// Used for requests that carry no token of their own.
+    Default,
+    /// Used by the agent for its internal operations.
+    Agent,
+    /// Lets operators act on the agent when the servers cannot be reached.
+    AgentRecovery,
+    /// Used by the servers of secondary datacenters to replicate ACLs.
+    Replication,
+}
+
+impl TokenKind {
+    fn path(self) -> &'static str {
+        match self {
+            TokenKind::Default => "default",
+            TokenKind::Agent => "agent",
+            TokenKind::AgentRecovery => "agent_recovery",
+            TokenKind::Replication => "replication",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct AgentTokenUpdate<'a> {
+    Token: &'a str,
+}
+
+#[derive(Serialize)]
+struct AgentCheckUpdate<'a> {
+    Status: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    Output: Option<&'a str>,
+}
+
+/// The response of `/v1/agent/self`. The configuration blocks and stats
+/// change shape between Consul versions and are kept as raw JSON.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentSelf {
+    pub Config: Value,
+    pub DebugConfig: Value,
+    pub Member: AgentMember,
+    pub Stats: Value,
+    pub Meta: Option<HashMap<String, String>>,
+}
+
+/// Host statistics of the agent's machine, as collected by gopsutil. Their
+/// layout depends on the platform, hence left untyped.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct AgentHostInfo {
+    pub Memory: Value,
+    pub CPU: Value,
+    pub Host: Value,
+    pub Disk: Value,
+    /// When the statistics were collected, in nanoseconds since the epoch.
+    pub CollectionTime: i64,
+    /// Failures collecting some of the statistics.
+    pub Errors: Option<Vec<String>>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct GaugeValue {
+    pub Name: String,
+    pub Value: f64,
+    pub Labels: HashMap<String, String>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PointValue {
+    pub Name: String,
+    pub Points: Vec<f64>,
+}
+
+/// A summary of the counter increments or samples over the interval.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct SampledValue {
+    pub Name: String,
+    pub Count: u64,
+    pub Rate: f64,
+    pub Sum: f64,
+    pub Min: f64,
+    pub Max: f64,
+    pub Mean: f64,
+    pub Stddev: f64,
+    pub Labels: HashMap<String, String>,
+}
+
+/// The metrics of the last completed interval, 10 seconds by default.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentMetrics {
+    pub Timestamp: String,
+    pub Gauges: Vec<GaugeValue>,
+    pub Points: Vec<PointValue>,
+    pub Counters: Vec<SampledValue>,
+    pub Samples: Vec<SampledValue>,
+}
+
 #[async_trait]
 pub trait Agent {
     async fn checks(&self) -> Result<HashMap<String, AgentCheck>>;
-    async fn members(&self, wan: bool) -> Result<AgentMember>;
+    async fn local_services(&self) -> Result<HashMap<String, AgentService>>;
+    async fn service_health_by_name(
+        &self,
+        name: &str,
+    ) -> Result<(Vec<AgentServiceChecksInfo>, QueryMeta)>;
+    async fn service_health_by_id(&self, id: &str) -> Result<(AgentServiceChecksInfo, QueryMeta)>;
+    async fn self_(&self) -> Result<AgentSelf>;
+    async fn read_config(&self) -> Result<Value>;
+    async fn host(&self) -> Result<AgentHostInfo>;
+    async fn metrics(&self) -> Result<AgentMetrics>;
+    async fn metrics_prometheus(&self) -> Result<String>;
+    async fn members(&self, wan: bool) -> Result<Vec<AgentMember>>;
+    async fn monitor(&self, loglevel: &str) -> Result<BoxStream<'static, Result<String>>>;
     async fn reload(&self) -> Result<()>;
     async fn maintenance_mode(&self, enable: bool, reason: Option<&str>) -> Result<()>;
     async fn join(&self, address: &str, wan: bool) -> Result<()>;
+    async fn join_many(&self, addresses: &[&str], wan: bool) -> Result<Vec<JoinResult>>;
     async fn leave(&self) -> Result<()>;
     async fn force_leave(&self) -> Result<()>;
+    async fn force_leave_node(&self, node: &str, prune: bool) -> Result<()>;
+    async fn register_service(&self, reg: &AgentServiceRegistration) -> Result<()>;
+    async fn register_service_if_changed(
+        &self,
+        reg: &AgentServiceRegistration,
+    ) -> Result<Registered>;
+    async fn deregister_service(&self, service_id: &str) -> Result<()>;
+    /// Blocks for `older_than` whenever a local service is critical, see
+    /// the implementation for `Client`.
+    async fn deregister_critical_services(&self, older_than: Duration) -> Result<Vec<String>>;
+    async fn service_maintenance(
+        &self,
+        service_id: &str,
+        enable: bool,
+        reason: Option<&str>,
+    ) -> Result<()>;
+    async fn register_check(&self, check: &AgentCheckRegistration) -> Result<()>;
+    async fn deregister_check(&self, check_id: &str) -> Result<()>;
+    async fn check_pass(&self, check_id: &str, note: Option<&str>) -> Result<()>;
+    async fn check_warn(&self, check_id: &str, note: Option<&str>) -> Result<()>;
+    async fn check_fail(&self, check_id: &str, note: Option<&str>) -> Result<()>;
+    async fn check_update(
+        &self,
+        check_id: &str,
+        status: CheckStatus,
+        output: Option<&str>,
+    ) -> Result<()>;
+    async fn update_token(&self, kind: TokenKind, token: &str) -> Result<()>;
+    async fn connect_leaf(
+        &self,
+        service: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(LeafCert, QueryMeta)>;
+    async fn connect_authorize(&self, req: &AuthorizeRequest) -> Result<AuthorizeResponse>;
 }
 
 #[async_trait]
 impl Agent for Client {
     /// https://www.consul.io/api/agent/check.html#list-checks
     async fn checks(&self) -> Result<HashMap<String, AgentCheck>> {
-        get("/v1/agent/checks", &self.config, HashMap::new(), None)
+        get("/v1/agent/checks", &self.config, Vec::new(), None)
             .await
             .map(|x| x.0)
     }
+    /// https://www.consul.io/api/agent/service.html#list-services
+    ///
+    /// The services registered with this agent, keyed by service ID, as
+    /// opposed to `Catalog::services` which covers the whole datacenter.
+    async fn local_services(&self) -> Result<HashMap<String, AgentService>> {
+        get("/v1/agent/services", &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+    /// https://www.consul.io/api/agent/service.html#get-local-service-health
+    ///
+    /// The health of every local instance of the service `name`. An unknown
+    /// service is an `ErrorKind::Consul` 404.
+    async fn service_health_by_name(
+        &self,
+        name: &str,
+    ) -> Result<(Vec<AgentServiceChecksInfo>, QueryMeta)> {
+        let path = format!("/v1/agent/health/service/name/{}", name);
+        let (mut infos, status, meta): (Vec<AgentServiceChecksInfo>, _, _) =
+            get_with_status(&path, &self.config, Vec::new(), None, &HEALTH_STATUSES).await?;
+        for info in infos.iter_mut() {
+            info.AggregatedStatus = aggregated_status(status, info.AggregatedStatus);
+        }
+        Ok((infos, meta))
+    }
+    /// https://www.consul.io/api/agent/service.html#get-local-service-health-by-its-id
+    async fn service_health_by_id(&self, id: &str) -> Result<(AgentServiceChecksInfo, QueryMeta)> {
+        let path = format!("/v1/agent/health/service/id/{}", id);
+        let (mut info, status, meta): (AgentServiceChecksInfo, _, _) =
+            get_with_status(&path, &self.config, Vec::new(), None, &HEALTH_STATUSES).await?;
+        info.AggregatedStatus = aggregated_status(status, info.AggregatedStatus);
+        Ok((info, meta))
+    }
+    /// https://www.consul.io/api/agent.html#read-configuration
+    async fn self_(&self) -> Result<AgentSelf> {
+        get("/v1/agent/self", &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+    /// https://www.consul.io/api/agent.html#read-configuration
+    ///
+    /// The `Config` and `DebugConfig` blocks of `self_`, left untyped since
+    /// their contents vary across Consul versions: e.g.
+    /// `config["Config"]["NodeName"]`.
+    async fn read_config(&self) -> Result<Value> {
+        let (mut agent, _): (Value, _) =
+            get("/v1/agent/self", &self.config, Vec::new(), None).await?;
+        let mut config = serde_json::Map::new();
+        for block in &["Config", "DebugConfig"] {
+            if let Some(value) = agent.get_mut(*block) {
+                config.insert(String::from(*block), value.take());
+            }
+        }
+        Ok(Value::Object(config))
+    }
+    /// https://www.consul.io/api/agent.html#read-host-information
+    async fn host(&self) -> Result<AgentHostInfo> {
+        get("/v1/agent/host", &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+    /// https://www.consul.io/api/agent.html#view-metrics
+    async fn metrics(&self) -> Result<AgentMetrics> {
+        get("/v1/agent/metrics", &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+    /// https://www.consul.io/api/agent.html#view-metrics
+    ///
+    /// The metrics in the Prometheus text exposition format, which requires
+    /// `telemetry.prometheus_retention_time` in the agent configuration.
+    async fn metrics_prometheus(&self) -> Result<String> {
+        let params = vec![(String::from("format"), String::from("prometheus"))];
+        let (body, _) = get_bytes("/v1/agent/metrics", &self.config, params, None).await?;
+        String::from_utf8(body).chain_err(|| "Failed to parse valid UTF8 for metrics")
+    }
     /// https://www.consul.io/api/agent.html#list-members
-    async fn members(&self, wan: bool) -> Result<AgentMember> {
-        let mut params = HashMap::new();
+    async fn members(&self, wan: bool) -> Result<Vec<AgentMember>> {
+        let mut params = Vec::new();
         if wan {
-            params.insert(String::from("wan"), String::from("1"));
+            params.push((String::from("wan"), String::from("1")));
         }
         get("/v1/agent/members", &self.config, params, None)
             .await
             .map(|x| x.0)
     }
+    /// https://www.consul.io/api/agent.html#stream-logs
+    ///
+    /// Streams the agent's log lines at `loglevel` (`trace`, `debug`,
+    /// `info`, `warn` or `err`) and above as they are written. The stream
+    /// never ends on its own; dropping it closes the connection.
+    async fn monitor(&self, loglevel: &str) -> Result<BoxStream<'static, Result<String>>> {
+        let params = vec![(String::from("loglevel"), loglevel.to_owned())];
+        let response = get_streaming("/v1/agent/monitor", &self.config, params, None).await?;
+        Ok(log_lines(response))
+    }
     /// https://www.consul.io/api/agent.html#reload-agent
     async fn reload(&self) -> Result<()> {
         put(
             "/v1/agent/reload",
             None as Option<&()>,
             &self.config,
-            HashMap::new(),
+            Vec::new(),
             None,
         )
         .await
         .map(|x| x.0)
     }
 
-    /// https://www.consul.io/api/agent.html#reload-agent
+    /// https://www.consul.io/api/agent.html#enable-maintenance-mode
     async fn maintenance_mode(&self, enable: bool, reason: Option<&str>) -> Result<()> {
-        let mut params = HashMap::new();
-        let enable_str = if enable {
-            String::from("true")
-        } else {
-            String::from("false")
-        };
-        params.insert(String::from("enabled"), enable_str);
-        if let Some(r) = reason {
-            params.insert(String::from("reason"), r.to_owned());
-        }
         put(
             "/v1/agent/maintenance",
             None as Option<&()>,
             &self.config,
-            params,
+            maintenance_params(enable, reason),
             None,
         )
         .await
@@ -116,10 +614,10 @@ impl Agent for Client {
     }
     ///https://www.consul.io/api/agent.html#join-agent
     async fn join(&self, address: &str, wan: bool) -> Result<()> {
-        let mut params = HashMap::new();
+        let mut params = Vec::new();
 
         if wan {
-            params.insert(String::from("wan"), String::from("true"));
+            params.push((String::from("wan"), String::from("true")));
         }
         let path = format!("/v1/agent/join/{}", address);
         put(&path, None as Option<&()>, &self.config, params, None)
@@ -127,13 +625,27 @@ impl Agent for Client {
             .map(|x| x.0)
     }
 
+    /// Joins each of `addresses` in turn, e.g. a list of seed servers, and
+    /// reports how each join went. A failed join does not stop the others,
+    /// it is only recorded in its `JoinResult`.
+    async fn join_many(&self, addresses: &[&str], wan: bool) -> Result<Vec<JoinResult>> {
+        let mut results = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            results.push(JoinResult {
+                address: address.to_string(),
+                result: self.join(address, wan).await,
+            });
+        }
+        Ok(results)
+    }
+
     /// https://www.consul.io/api/agent.html#graceful-leave-and-shutdown
     async fn leave(&self) -> Result<()> {
         put(
             "/v1/agent/leave",
             None as Option<&()>,
             &self.config,
-            HashMap::new(),
+            Vec::new(),
             None,
         )
         .await
@@ -146,10 +658,306 @@ impl Agent for Client {
             "/v1/agent/force-leave",
             None as Option<&()>,
             &self.config,
-            HashMap::new(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/agent.html#force-leave-and-shutdown
+    ///
+    /// Moves the failed `node` to the `left` state, or with `prune` removes
+    /// it from the member list right away.
+    async fn force_leave_node(&self, node: &str, prune: bool) -> Result<()> {
+        let mut params = Vec::new();
+        if prune {
+            params.push((String::from("prune"), String::from("1")));
+        }
+        let path = format!("/v1/agent/force-leave/{}", node);
+        put(&path, None as Option<&()>, &self.config, params, None)
+            .await
+            .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/agent/service.html#register-service
+    async fn register_service(&self, reg: &AgentServiceRegistration) -> Result<()> {
+        put(
+            "/v1/agent/service/register",
+            Some(reg),
+            &self.config,
+            Vec::new(),
+            None,
+        )
+        .await
+        .map(|x| x.0)
+    }
+
+    /// Like `register_service`, but first compares `reg` with the service
+    /// already registered under its ID (its `Name` when `ID` is `None`) and
+    /// skips the write when they match, sparing the catalog the churn of
+    /// re-registrations in a loop. Only the service's own fields are
+    /// compared: to change just its checks, use `register_service`.
+    async fn register_service_if_changed(
+        &self,
+        reg: &AgentServiceRegistration,
+    ) -> Result<Registered> {
+        let id = reg.ID.as_deref().unwrap_or(&reg.Name);
+        let registered = match self.local_services().await?.remove(id) {
+            Some(service) if reg.matches(&service) => return Ok(Registered::Unchanged),
+            Some(_) => Registered::Updated,
+            None => Registered::Created,
+        };
+        self.register_service(reg).await?;
+        Ok(registered)
+    }
+
+    /// https://www.consul.io/api/agent/service.html#deregister-service
+    async fn deregister_service(&self, service_id: &str) -> Result<()> {
+        let path = format!("/v1/agent/service/deregister/{}", service_id);
+        put(&path, None as Option<&()>, &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+
+    /// Deregisters the local services with a check that has been critical
+    /// for `older_than`, returning their IDs; a client-driven take on
+    /// `DeregisterCriticalServiceAfter`.
+    ///
+    /// Consul does not tell since when a check is critical, so the node's
+    /// checks are sampled twice, `older_than` apart, and only services with a
+    /// check critical in both samples and left untouched in between, its
+    /// `ModifyIndex` unchanged, are removed: a check that recovered and
+    /// failed again meanwhile has a new index. The call sleeps for
+    /// `older_than` whenever a service is critical in the first sample.
+    ///
+    /// Services registered in the catalog rather than with this agent are
+    /// left alone, the agent cannot deregister them.
+    async fn deregister_critical_services(&self, older_than: Duration) -> Result<Vec<String>> {
+        let node = self.self_().await?.Member.Name;
+        let (checks, _) = Health::node(self, node.as_str(), None).await?;
+        let before = critical_checks(&checks);
+        if before.is_empty() {
+            return Ok(Vec::new());
+        }
+        tokio::time::sleep(older_than).await;
+        let (checks, _) = Health::node(self, node.as_str(), None).await?;
+        let after = critical_checks(&checks);
+        let local = self.local_services().await?;
+        let removed: BTreeSet<String> = before
+            .intersection(&after)
+            .map(|(service_id, _, _)| service_id.clone())
+            .filter(|service_id| local.contains_key(service_id))
+            .collect();
+        let removed: Vec<String> = removed.into_iter().collect();
+        for service_id in &removed {
+            self.deregister_service(service_id).await?;
+        }
+        Ok(removed)
+    }
+
+    /// https://www.consul.io/api/agent/service.html#enable-maintenance-mode
+    ///
+    /// Puts a single service instance in maintenance, failing its health
+    /// until disabled again, while the rest of the node keeps serving.
+    async fn service_maintenance(
+        &self,
+        service_id: &str,
+        enable: bool,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        let path = format!("/v1/agent/service/maintenance/{}", service_id);
+        put(
+            &path,
+            None as Option<&()>,
+            &self.config,
+            maintenance_params(enable, reason),
             None,
         )
         .await
         .map(|x| x.0)
     }
+
+    /// https://www.consul.io/api/agent/check.html#register-check
+    async fn register_check(&self, check: &AgentCheckRegistration) -> Result<()> {
+        put(
+            "/v1/agent/check/register",
+            Some(check),
+            &self.config,
+            Vec::new(),
+            None,
+        )
+        .await
+        .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/agent/check.html#deregister-check
+    async fn deregister_check(&self, check_id: &str) -> Result<()> {
+        let path = format!("/v1/agent/check/deregister/{}", check_id);
+        put(&path, None as Option<&()>, &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/agent/check.html#ttl-check-pass
+    async fn check_pass(&self, check_id: &str, note: Option<&str>) -> Result<()> {
+        ttl_check_transition(&self.config, "pass", check_id, note).await
+    }
+
+    /// https://www.consul.io/api/agent/check.html#ttl-check-warn
+    async fn check_warn(&self, check_id: &str, note: Option<&str>) -> Result<()> {
+        ttl_check_transition(&self.config, "warn", check_id, note).await
+    }
+
+    /// https://www.consul.io/api/agent/check.html#ttl-check-fail
+    async fn check_fail(&self, check_id: &str, note: Option<&str>) -> Result<()> {
+        ttl_check_transition(&self.config, "fail", check_id, note).await
+    }
+
+    /// https://www.consul.io/api/agent/check.html#ttl-check-update
+    ///
+    /// Unlike the pass/warn/fail routes, which carry the note in the query
+    /// string, the output is sent in the body and kept in full.
+    async fn check_update(
+        &self,
+        check_id: &str,
+        status: CheckStatus,
+        output: Option<&str>,
+    ) -> Result<()> {
+        let path = format!("/v1/agent/check/update/{}", check_id);
+        let update = AgentCheckUpdate {
+            Status: status,
+            Output: output,
+        };
+        put(&path, Some(&update), &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/agent.html#update-acl-tokens
+    ///
+    /// Replaces one of the agent's tokens without a restart. Unless the
+    /// agent runs with `acl.enable_token_persistence`, the new token only
+    /// lasts until the agent restarts. `TokenKind::AgentRecovery` needs
+    /// Consul 1.11 or later.
+    async fn update_token(&self, kind: TokenKind, token: &str) -> Result<()> {
+        let path = format!("/v1/agent/token/{}", kind.path());
+        let update = AgentTokenUpdate { Token: token };
+        put(&path, Some(&update), &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/agent/connect.html#service-leaf-certificate
+    ///
+    /// The certificate the agent keeps for `service`, generated on the first
+    /// request and renewed ahead of its expiry. Block on the `last_index` to
+    /// be handed the renewed certificate as soon as it is issued.
+    async fn connect_leaf(
+        &self,
+        service: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(LeafCert, QueryMeta)> {
+        let path = format!("/v1/agent/connect/ca/leaf/{}", service);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/agent/connect.html#authorize
+    ///
+    /// Checks the intentions, and that the client's certificate was issued by
+    /// the cluster, for a connection to `req.Target`. A denial is not an
+    /// error: `Authorized` is `false` and `Reason` tells why.
+    async fn connect_authorize(&self, req: &AuthorizeRequest) -> Result<AuthorizeResponse> {
+        post(
+            "/v1/agent/connect/authorize",
+            Some(req),
+            &self.config,
+            Vec::new(),
+            None,
+        )
+        .await
+        .map(|x| x.0)
+    }
+}
+
+/// Splits a streamed body into lines, without their line terminator.
+fn log_lines(response: Response) -> BoxStream<'static, Result<String>> {
+    let state = (Some(response), Vec::new());
+    stream::unfold(state, |(mut response, mut buffer)| async move {
+        loop {
+            if let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                let rest = buffer.split_off(end + 1);
+                let line = String::from_utf8_lossy(&buffer).trim_end().to_owned();
+                return Some((Ok(line), (response, rest)));
+            }
+            let chunk = match response.as_mut() {
+                Some(r) => r.chunk().await,
+                None => return None,
+            };
+            match chunk {
+                Ok(Some(chunk)) => buffer.extend_from_slice(&chunk),
+                // The body ended, flush what is left of the last line
+                Ok(None) if buffer.is_empty() => return None,
+                Ok(None) => {
+                    let line = String::from_utf8_lossy(&buffer).trim_end().to_owned();
+                    return Some((Ok(line), (None, Vec::new())));
+                }
+                Err(e) => {
+                    let error = Error::with_chain(e, "Failed to read log stream");
+                    return Some((Err(error), (None, Vec::new())));
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+/// The local service health endpoints encode the aggregated status in the
+/// HTTP status: 200 passing, 429 warning and 503 critical.
+const HEALTH_STATUSES: [StatusCode; 2] = [
+    StatusCode::TOO_MANY_REQUESTS,
+    StatusCode::SERVICE_UNAVAILABLE,
+];
+
+fn aggregated_status(status: StatusCode, reported: CheckStatus) -> CheckStatus {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => CheckStatus::Warning,
+        // Maintenance is reported as critical too, keep the finer status
+        StatusCode::SERVICE_UNAVAILABLE if reported == CheckStatus::Maintenance => reported,
+        StatusCode::SERVICE_UNAVAILABLE => CheckStatus::Critical,
+        _ => CheckStatus::Passing,
+    }
+}
+
+fn maintenance_params(enable: bool, reason: Option<&str>) -> Vec<(String, String)> {
+    let mut params = vec![(String::from("enable"), enable.to_string())];
+    if let Some(r) = reason {
+        params.push((String::from("reason"), r.to_owned()));
+    }
+    params
+}
+
+/// The critical service checks, as service ID, check ID and `ModifyIndex`.
+fn critical_checks(checks: &[HealthCheck]) -> HashSet<(String, String, u64)> {
+    checks
+        .iter()
+        .filter(|c| c.Status == CheckStatus::Critical && !c.ServiceID.is_empty())
+        .map(|c| (c.ServiceID.clone(), c.CheckID.clone(), c.ModifyIndex))
+        .collect()
+}
+
+async fn ttl_check_transition(
+    config: &Config,
+    transition: &str,
+    check_id: &str,
+    note: Option<&str>,
+) -> Result<()> {
+    let mut params = Vec::new();
+    if let Some(note) = note {
+        params.push((String::from("note"), note.to_owned()));
+    }
+    let path = format!("/v1/agent/check/{}/{}", transition, check_id);
+    put(&path, None as Option<&()>, config, params, None)
+        .await
+        .map(|x| x.0)
 }
@@ -4,79 +4,123 @@ use async_trait::async_trait;
 
 use crate::agent::{AgentCheck, AgentService};
 use crate::errors::Result;
-use crate::request::{get, put};
+use crate::health::ServiceEntry;
+use crate::request::{get, get_vec, put};
 use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
 
-#[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct Weights {
-    Passing: u32,
-    Warning: u32,
+    pub Passing: u32,
+    pub Warning: u32,
 }
 
 #[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct Node {
-    ID: String,
-    Node: String,
-    Address: String,
-    Datacenter: String,
-    TaggedAddresses: HashMap<String, String>,
-    Meta: HashMap<String, String>,
-    CreateIndex: u64,
-    ModifyIndex: u64,
+    pub ID: String,
+    pub Node: String,
+    pub Address: String,
+    pub Datacenter: String,
+    pub TaggedAddresses: Option<HashMap<String, String>>,
+    pub Meta: Option<HashMap<String, String>>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
 }
 
 #[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct CatalogService {
-    ID: String,
-    Node: String,
-    Address: String,
-    Datacenter: String,
-    TaggedAddresses: HashMap<String, String>,
-    NodeMeta: HashMap<String, String>,
-    ServiceID: String,
-    ServiceName: String,
-    ServiceAddress: String,
-    ServiceTags: Vec<String>,
-    ServiceMeta: HashMap<String, String>,
-    ServicePort: u32,
-    ServiceWeights: Weights,
-    ServiceEnableTagOverride: bool,
-    CreateIndex: u64,
-    ModifyIndex: u64,
+    pub ID: String,
+    pub Node: String,
+    pub Address: String,
+    pub Datacenter: String,
+    pub TaggedAddresses: Option<HashMap<String, String>>,
+    pub NodeMeta: Option<HashMap<String, String>>,
+    pub ServiceID: String,
+    pub ServiceName: String,
+    pub ServiceAddress: String,
+    pub ServiceTags: Option<Vec<String>>,
+    pub ServiceMeta: Option<HashMap<String, String>>,
+    pub ServicePort: u32,
+    pub ServiceWeights: Weights,
+    pub ServiceEnableTagOverride: bool,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
 }
 
 #[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct CatalogNode {
-    Node: Option<Node>,
-    Services: HashMap<String, AgentService>,
+    pub Node: Option<Node>,
+    pub Services: HashMap<String, AgentService>,
+}
+
+/// Like `CatalogNode`, but keeps every service instance even when several
+/// share an ID across namespaces.
+#[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CatalogNodeServiceList {
+    pub Node: Option<Node>,
+    pub Services: Vec<AgentService>,
+}
+
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CompoundServiceName {
+    pub Name: String,
+    pub Namespace: String,
+    pub Partition: String,
+}
+
+/// A service fronted by an ingress or terminating gateway.
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct GatewayService {
+    pub Gateway: CompoundServiceName,
+    pub Service: CompoundServiceName,
+    /// `ingress-gateway` or `terminating-gateway`.
+    pub GatewayKind: String,
+    /// The listener port, for ingress gateways.
+    pub Port: u16,
+    pub Protocol: String,
+    /// The hosts the service is exposed under, for ingress gateways.
+    pub Hosts: Option<Vec<String>>,
+    /// TLS settings towards the service, for terminating gateways.
+    pub CAFile: String,
+    pub CertFile: String,
+    pub KeyFile: String,
+    pub SNI: String,
+    /// Whether the service was matched by a `*` in the gateway's entry.
+    pub FromWildcard: bool,
 }
 
 #[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct CatalogRegistration {
-    ID: String,
-    Node: String,
-    Address: String,
-    TaggedAddresses: HashMap<String, String>,
-    NodeMeta: HashMap<String, String>,
-    Datacenter: String,
-    Service: Option<AgentService>,
-    Check: Option<AgentCheck>,
-    SkipNodeUpdate: bool,
+    pub ID: String,
+    pub Node: String,
+    pub Address: String,
+    /// Alternative addresses of the node, keyed by kind such as `lan` or
+    /// `wan`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TaggedAddresses: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NodeMeta: Option<HashMap<String, String>>,
+    pub Datacenter: String,
+    pub Service: Option<AgentService>,
+    pub Check: Option<AgentCheck>,
+    pub SkipNodeUpdate: bool,
 }
 
 #[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct CatalogDeregistration {
-    Node: String,
-    Address: String,
-    Datacenter: String,
-    ServiceID: String,
-    CheckID: String,
+    pub Node: String,
+    pub Address: String,
+    pub Datacenter: String,
+    pub ServiceID: String,
+    pub CheckID: String,
 }
 
 #[async_trait]
@@ -91,12 +135,55 @@ pub trait Catalog {
         dereg: &CatalogDeregistration,
         q: Option<&WriteOptions>,
     ) -> Result<((), WriteMeta)>;
+    async fn catalog_deregister_service(
+        &self,
+        node: &str,
+        service_id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+    async fn catalog_deregister_check(
+        &self,
+        node: &str,
+        check_id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
     async fn datacenters(&self) -> Result<(Vec<String>, QueryMeta)>;
     async fn nodes(&self, q: Option<&QueryOptions>) -> Result<(Vec<Node>, QueryMeta)>;
+    async fn node(
+        &self,
+        node: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<CatalogNode>, QueryMeta)>;
+    async fn node_services(
+        &self,
+        node: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<CatalogNodeServiceList>, QueryMeta)>;
+    async fn gateway_services(
+        &self,
+        gateway: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<GatewayService>, QueryMeta)>;
     async fn services(
         &self,
         q: Option<&QueryOptions>,
     ) -> Result<(HashMap<String, Vec<String>>, QueryMeta)>;
+    async fn service(
+        &self,
+        name: &str,
+        tag: Option<&str>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<CatalogService>, QueryMeta)>;
+    async fn connect(
+        &self,
+        service: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<CatalogService>, QueryMeta)>;
+    async fn service_nearest(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<ServiceEntry>, QueryMeta)>;
 }
 
 #[async_trait]
@@ -108,13 +195,48 @@ impl Catalog for Client {
         q: Option<&WriteOptions>,
     ) -> Result<((), WriteMeta)> {
         put(
-            "/v1/session/create",
+            "/v1/catalog/register",
             Some(reg),
             &self.config,
-            HashMap::new(),
+            Vec::new(),
             q,
         )
         .await
+        .map(|x: (bool, WriteMeta)| ((), x.1))
+    }
+
+    /// https://www.consul.io/api/catalog.html#deregister-entity
+    ///
+    /// Removes one service, and its checks, from `node`.
+    async fn catalog_deregister_service(
+        &self,
+        node: &str,
+        service_id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let dereg = CatalogDeregistration {
+            Node: node.to_owned(),
+            ServiceID: service_id.to_owned(),
+            ..Default::default()
+        };
+        self.deregister(&dereg, q).await
+    }
+
+    /// https://www.consul.io/api/catalog.html#deregister-entity
+    ///
+    /// Removes one check from `node`.
+    async fn catalog_deregister_check(
+        &self,
+        node: &str,
+        check_id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let dereg = CatalogDeregistration {
+            Node: node.to_owned(),
+            CheckID: check_id.to_owned(),
+            ..Default::default()
+        };
+        self.deregister(&dereg, q).await
     }
 
     /// https://www.consul.io/api/catalog.html#deregister-entity
@@ -127,32 +249,115 @@ impl Catalog for Client {
             "/v1/catalog/deregister",
             Some(dereg),
             &self.config,
-            HashMap::new(),
+            Vec::new(),
             q,
         )
         .await
+        .map(|x: (bool, WriteMeta)| ((), x.1))
     }
 
     /// https://www.consul.io/api/catalog.html#list-datacenters
     async fn datacenters(&self) -> Result<(Vec<String>, QueryMeta)> {
-        get(
-            "/v1/catalog/datacenters",
-            &self.config,
-            HashMap::new(),
-            None,
-        )
-        .await
+        get("/v1/catalog/datacenters", &self.config, Vec::new(), None).await
     }
 
     /// https://www.consul.io/api/catalog.html#list-nodes
     async fn nodes(&self, q: Option<&QueryOptions>) -> Result<(Vec<Node>, QueryMeta)> {
-        get("/v1/catalog/nodes", &self.config, HashMap::new(), q).await
+        get("/v1/catalog/nodes", &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/catalog.html#list-services-for-node
+    ///
+    /// Consul answers `null` for an unknown node, which maps to `None`.
+    async fn node(
+        &self,
+        node: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<CatalogNode>, QueryMeta)> {
+        let path = format!("/v1/catalog/node/{}", node);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/catalog.html#list-services-for-node
+    ///
+    /// The array-shaped variant of `node`, available since Consul 1.7. An
+    /// unknown node maps to `None`.
+    async fn node_services(
+        &self,
+        node: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<CatalogNodeServiceList>, QueryMeta)> {
+        let path = format!("/v1/catalog/node-services/{}", node);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/catalog.html#list-services-for-gateway
+    ///
+    /// Available since Consul 1.8.
+    async fn gateway_services(
+        &self,
+        gateway: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<GatewayService>, QueryMeta)> {
+        let path = format!("/v1/catalog/gateway-services/{}", gateway);
+        get_vec(&path, &self.config, Vec::new(), q).await
     }
 
     async fn services(
         &self,
         q: Option<&QueryOptions>,
     ) -> Result<(HashMap<String, Vec<String>>, QueryMeta)> {
-        get("/v1/catalog/services", &self.config, HashMap::new(), q).await
+        get("/v1/catalog/services", &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/catalog.html#list-nodes-for-service
+    ///
+    /// An unknown service name yields an empty list.
+    async fn service(
+        &self,
+        name: &str,
+        tag: Option<&str>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<CatalogService>, QueryMeta)> {
+        let mut params = Vec::new();
+        if let Some(tag) = tag {
+            params.push((String::from("tag"), tag.to_owned()));
+        }
+        let path = format!("/v1/catalog/service/{}", name);
+        get(&path, &self.config, params, q).await
+    }
+
+    /// https://www.consul.io/api/catalog.html#list-nodes-for-connect-capable-service
+    ///
+    /// Only the instances reachable over Connect: the sidecar proxies of
+    /// `service`, listed with their own address and port, and its
+    /// Connect-native instances.
+    async fn connect(
+        &self,
+        service: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<CatalogService>, QueryMeta)> {
+        let path = format!("/v1/catalog/connect/{}", service);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/health.html#list-nodes-for-service
+    ///
+    /// The passing instance closest to the local agent by network
+    /// coordinates, `None` when no instance is passing. Instances on nodes
+    /// without coordinates sort last. `QueryOptions::near` measures from
+    /// another node instead.
+    async fn service_nearest(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<ServiceEntry>, QueryMeta)> {
+        let mut params = vec![(String::from("passing"), String::from("1"))];
+        if q.and_then(|q| q.near.as_ref()).is_none() {
+            params.push((String::from("near"), String::from("_agent")));
+        }
+        let path = format!("/v1/health/service/{}", name);
+        let (entries, meta): (Vec<ServiceEntry>, _) = get(&path, &self.config, params, q).await?;
+        Ok((entries.into_iter().next(), meta))
     }
 }
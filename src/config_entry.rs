@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::errors::Result;
+use crate::request::{delete, get, put};
+use crate::{Client, GoDuration, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+
+/// https://www.consul.io/docs/agent/config-entries/service-defaults.html
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceDefaultsEntry {
+    pub Name: String,
+    /// `tcp` (the default), `http`, `http2` or `grpc`; the L7 entries only
+    /// apply to services with an HTTP-based protocol.
+    pub Protocol: String,
+    pub MeshGateway: Option<Value>,
+    pub ExternalSNI: String,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+/// https://www.consul.io/docs/agent/config-entries/proxy-defaults.html
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ProxyDefaultsEntry {
+    /// Always `global`, there is a single proxy-defaults entry.
+    pub Name: String,
+    /// Opaque configuration handed to every proxy.
+    pub Config: Option<HashMap<String, Value>>,
+    pub MeshGateway: Option<Value>,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceResolverSubset {
+    /// A filter expression on the service instances, e.g.
+    /// `Service.Meta.version == v2`.
+    pub Filter: String,
+    pub OnlyPassing: bool,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceResolverRedirect {
+    pub Service: String,
+    pub ServiceSubset: String,
+    pub Namespace: String,
+    pub Datacenter: String,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceResolverFailover {
+    pub Service: String,
+    pub ServiceSubset: String,
+    pub Namespace: String,
+    pub Datacenters: Option<Vec<String>>,
+}
+
+/// https://www.consul.io/docs/agent/config-entries/service-resolver.html
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceResolverEntry {
+    pub Name: String,
+    pub DefaultSubset: String,
+    pub Subsets: Option<HashMap<String, ServiceResolverSubset>>,
+    pub Redirect: Option<ServiceResolverRedirect>,
+    /// Keyed by subset name, `*` applying to every subset.
+    pub Failover: Option<HashMap<String, ServiceResolverFailover>>,
+    pub ConnectTimeout: Option<GoDuration>,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceRouteHTTPMatch {
+    pub PathExact: String,
+    pub PathPrefix: String,
+    pub PathRegex: String,
+    pub Methods: Option<Vec<String>>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceRouteMatch {
+    pub HTTP: Option<ServiceRouteHTTPMatch>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceRouteDestination {
+    pub Service: String,
+    pub ServiceSubset: String,
+    pub Namespace: String,
+    pub PrefixRewrite: String,
+    pub RequestTimeout: Option<GoDuration>,
+    pub NumRetries: u32,
+    pub RetryOnConnectFailure: bool,
+    pub RetryOnStatusCodes: Option<Vec<u16>>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceRoute {
+    pub Match: Option<ServiceRouteMatch>,
+    pub Destination: Option<ServiceRouteDestination>,
+}
+
+/// https://www.consul.io/docs/agent/config-entries/service-router.html
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceRouterEntry {
+    pub Name: String,
+    /// Tried in order, the first matching route wins.
+    pub Routes: Option<Vec<ServiceRoute>>,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceSplit {
+    /// Percentage of the traffic, the weights of an entry adding up to 100.
+    pub Weight: f32,
+    pub Service: String,
+    pub ServiceSubset: String,
+    pub Namespace: String,
+}
+
+/// https://www.consul.io/docs/agent/config-entries/service-splitter.html
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceSplitterEntry {
+    pub Name: String,
+    pub Splits: Vec<ServiceSplit>,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+/// A configuration entry, tagged by its `Kind`.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(tag = "Kind")]
+pub enum Entry {
+    #[serde(rename = "service-defaults")]
+    ServiceDefaults(ServiceDefaultsEntry),
+    #[serde(rename = "proxy-defaults")]
+    ProxyDefaults(ProxyDefaultsEntry),
+    #[serde(rename = "service-resolver")]
+    ServiceResolver(ServiceResolverEntry),
+    #[serde(rename = "service-router")]
+    ServiceRouter(ServiceRouterEntry),
+    #[serde(rename = "service-splitter")]
+    ServiceSplitter(ServiceSplitterEntry),
+}
+
+impl Entry {
+    /// The `Kind` to pass to `ConfigEntry::get`, `list` and `delete`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Entry::ServiceDefaults(_) => "service-defaults",
+            Entry::ProxyDefaults(_) => "proxy-defaults",
+            Entry::ServiceResolver(_) => "service-resolver",
+            Entry::ServiceRouter(_) => "service-router",
+            Entry::ServiceSplitter(_) => "service-splitter",
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Entry::ServiceDefaults(e) => &e.Name,
+            Entry::ProxyDefaults(e) => &e.Name,
+            Entry::ServiceResolver(e) => &e.Name,
+            Entry::ServiceRouter(e) => &e.Name,
+            Entry::ServiceSplitter(e) => &e.Name,
+        }
+    }
+}
+
+#[async_trait]
+pub trait ConfigEntry {
+    async fn apply(&self, entry: &Entry, q: Option<&WriteOptions>) -> Result<(bool, WriteMeta)>;
+    async fn get(
+        &self,
+        kind: &str,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Entry, QueryMeta)>;
+    async fn list(&self, kind: &str, q: Option<&QueryOptions>) -> Result<(Vec<Entry>, QueryMeta)>;
+    async fn delete(
+        &self,
+        kind: &str,
+        name: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+}
+
+#[async_trait]
+impl ConfigEntry for Client {
+    /// https://www.consul.io/api/config.html#apply-configuration
+    ///
+    /// Creates or replaces the entry with the same kind and name.
+    async fn apply(&self, entry: &Entry, q: Option<&WriteOptions>) -> Result<(bool, WriteMeta)> {
+        put("/v1/config", Some(entry), &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/config.html#get-configuration
+    async fn get(
+        &self,
+        kind: &str,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Entry, QueryMeta)> {
+        let path = format!("/v1/config/{}/{}", kind, name);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/config.html#list-configurations
+    async fn list(&self, kind: &str, q: Option<&QueryOptions>) -> Result<(Vec<Entry>, QueryMeta)> {
+        let path = format!("/v1/config/{}", kind);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/config.html#delete-configuration
+    async fn delete(
+        &self,
+        kind: &str,
+        name: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let path = format!("/v1/config/{}/{}", kind, name);
+        delete(&path, None as Option<&()>, &self.config, Vec::new(), q).await
+    }
+}
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use async_trait::async_trait;
 use serde_json::Value;
 
@@ -7,41 +5,53 @@ use crate::errors::Result;
 use crate::request::{get, put};
 use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
 
-#[derive(Default, Serialize, Deserialize, Debug)]
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct CAConfig {
-    Provider: String,
-    Config: Value,
-    CreateIndex: u64,
-    ModifyIndex: u64,
+    /// `consul` for the built-in CA, or e.g. `vault`.
+    pub Provider: String,
+    /// Provider-specific settings.
+    pub Config: Value,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
 }
 
-#[derive(Default, Serialize, Deserialize, Debug)]
+/// The trust bundle of the cluster.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 #[allow(clippy::upper_case_acronyms)]
-pub struct CARootList {
-    ActiveRootID: String,
-    TrustDomain: String,
-    Roots: Vec<CARoot>,
+pub struct CARoots {
+    /// The `ID` of the root currently signing leaf certificates.
+    pub ActiveRootID: String,
+    /// The SPIFFE trust domain, `<cluster id>.consul`.
+    pub TrustDomain: String,
+    pub Roots: Vec<CARoot>,
 }
 
-#[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+/// The former name of `CARoots`.
+#[deprecated(note = "renamed to `CARoots`")]
+pub type CARootList = CARoots;
+
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct CARoot {
-    ID: String,
-    Name: String,
-    RootCert: String,
-    Active: bool,
-    CreateIndex: u64,
-    ModifyIndex: u64,
+    pub ID: String,
+    pub Name: String,
+    /// The PEM-encoded root certificate.
+    pub RootCert: String,
+    pub Active: bool,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
 }
 
 #[allow(clippy::upper_case_acronyms)]
 #[async_trait]
 pub trait ConnectCA {
-    async fn ca_roots(&self, q: Option<&QueryOptions>) -> Result<(CARootList, QueryMeta)>;
+    async fn ca_roots(&self, q: Option<&QueryOptions>) -> Result<(CARoots, QueryMeta)>;
     async fn ca_get_config(&self, q: Option<&QueryOptions>) -> Result<(CAConfig, QueryMeta)>;
     async fn ca_set_config(
         &self,
@@ -53,22 +63,19 @@ pub trait ConnectCA {
 #[async_trait]
 impl ConnectCA for Client {
     /// https://www.consul.io/api/connect/ca.html#list-ca-root-certificates
-    async fn ca_roots(&self, q: Option<&QueryOptions>) -> Result<(CARootList, QueryMeta)> {
-        get("/v1/connect/ca/roots", &self.config, HashMap::new(), q).await
+    async fn ca_roots(&self, q: Option<&QueryOptions>) -> Result<(CARoots, QueryMeta)> {
+        get("/v1/connect/ca/roots", &self.config, Vec::new(), q).await
     }
 
     /// https://www.consul.io/api/connect/ca.html#get-ca-configuration
     async fn ca_get_config(&self, q: Option<&QueryOptions>) -> Result<(CAConfig, QueryMeta)> {
-        get(
-            "/v1/connect/ca/configuration",
-            &self.config,
-            HashMap::new(),
-            q,
-        )
-        .await
+        get("/v1/connect/ca/configuration", &self.config, Vec::new(), q).await
     }
 
     /// https://www.consul.io/api/connect/ca.html#update-ca-configuration
+    ///
+    /// Changing the provider or its keys rotates the root, the old root is
+    /// kept in `CARoots` until the leaf certificates it signed expire.
     async fn ca_set_config(
         &self,
         conf: &CAConfig,
@@ -78,7 +85,7 @@ impl ConnectCA for Client {
             "/v1/connect/ca/configuration",
             Some(conf),
             &self.config,
-            HashMap::new(),
+            Vec::new(),
             q,
         )
         .await
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::errors::Result;
+use crate::request::{delete, get, post};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+
+#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum IntentionAction {
+    Allow,
+    #[default]
+    Deny,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Intention {
+    /// Generated by Consul on creation.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ID: String,
+    pub Description: String,
+    /// The source service, `*` matching every service.
+    pub SourceName: String,
+    /// The destination service, `*` matching every service.
+    pub DestinationName: String,
+    pub SourceType: String,
+    pub Action: IntentionAction,
+    /// Computed by Consul, intentions with a higher precedence are matched
+    /// first.
+    #[serde(skip_serializing)]
+    pub Precedence: u32,
+    pub Meta: Option<HashMap<String, String>>,
+    #[serde(skip_serializing)]
+    pub CreatedAt: Option<String>,
+    #[serde(skip_serializing)]
+    pub UpdatedAt: Option<String>,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+#[derive(Deserialize)]
+struct IntentionID {
+    ID: String,
+}
+
+#[derive(Deserialize)]
+struct IntentionCheck {
+    Allowed: bool,
+}
+
+#[async_trait]
+pub trait ConnectIntentions {
+    async fn intention_create(
+        &self,
+        intention: &Intention,
+        q: Option<&WriteOptions>,
+    ) -> Result<(String, WriteMeta)>;
+    async fn intention_list(&self, q: Option<&QueryOptions>)
+        -> Result<(Vec<Intention>, QueryMeta)>;
+    async fn intention_get(
+        &self,
+        id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Intention, QueryMeta)>;
+    async fn intention_delete(&self, id: &str, q: Option<&WriteOptions>)
+        -> Result<((), WriteMeta)>;
+    async fn intention_match(
+        &self,
+        by: &str,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<Intention>, QueryMeta)>;
+    async fn intention_check(&self, source: &str, destination: &str) -> Result<bool>;
+}
+
+#[async_trait]
+impl ConnectIntentions for Client {
+    /// https://www.consul.io/api/connect/intentions.html#create-intention
+    ///
+    /// Returns the ID of the new intention.
+    async fn intention_create(
+        &self,
+        intention: &Intention,
+        q: Option<&WriteOptions>,
+    ) -> Result<(String, WriteMeta)> {
+        post(
+            "/v1/connect/intentions",
+            Some(intention),
+            &self.config,
+            Vec::new(),
+            q,
+        )
+        .await
+        .map(|x: (IntentionID, WriteMeta)| (x.0.ID, x.1))
+    }
+
+    /// https://www.consul.io/api/connect/intentions.html#list-intentions
+    async fn intention_list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<Intention>, QueryMeta)> {
+        get("/v1/connect/intentions", &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/connect/intentions.html#read-specific-intention
+    async fn intention_get(
+        &self,
+        id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Intention, QueryMeta)> {
+        let path = format!("/v1/connect/intentions/{}", id);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/connect/intentions.html#delete-intention
+    async fn intention_delete(
+        &self,
+        id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let path = format!("/v1/connect/intentions/{}", id);
+        delete(&path, None as Option<&()>, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/connect/intentions.html#list-matching-intentions
+    ///
+    /// Lists the intentions whose `source` or `destination`, per `by`,
+    /// matches the service `name`, ordered by decreasing precedence.
+    async fn intention_match(
+        &self,
+        by: &str,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<Intention>, QueryMeta)> {
+        let params = vec![
+            (String::from("by"), by.to_owned()),
+            (String::from("name"), name.to_owned()),
+        ];
+        get("/v1/connect/intentions/match", &self.config, params, q)
+            .await
+            .map(|x: (HashMap<String, Vec<Intention>>, QueryMeta)| {
+                let (mut matches, meta) = x;
+                (matches.remove(name).unwrap_or_default(), meta)
+            })
+    }
+
+    /// https://www.consul.io/api/connect/intentions.html#check-intention-result
+    ///
+    /// Whether a connection from `source` to `destination` is allowed, taking
+    /// the default ACL policy into account when no intention matches.
+    async fn intention_check(&self, source: &str, destination: &str) -> Result<bool> {
+        let params = vec![
+            (String::from("source"), source.to_owned()),
+            (String::from("destination"), destination.to_owned()),
+        ];
+        get("/v1/connect/intentions/check", &self.config, params, None)
+            .await
+            .map(|x: (IntentionCheck, QueryMeta)| x.0.Allowed)
+    }
+}
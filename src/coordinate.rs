@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::errors::Result;
+use crate::request::get;
+use crate::{Client, QueryMeta, QueryOptions};
+
+/// A network coordinate computed by Serf's Vivaldi algorithm, in seconds.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct NetworkCoordinate {
+    pub Vec: Vec<f64>,
+    pub Error: f64,
+    pub Adjustment: f64,
+    pub Height: f64,
+}
+
+impl NetworkCoordinate {
+    /// Estimates the round trip time to `other`: the Euclidean distance
+    /// between the vectors plus both heights, corrected by both adjustments
+    /// unless that would make the estimate negative.
+    ///
+    /// Returns `None` when the coordinates don't have the same
+    /// dimensionality, e.g. when they come from different Serf pools.
+    pub fn distance_to(&self, other: &NetworkCoordinate) -> Option<Duration> {
+        if self.Vec.len() != other.Vec.len() {
+            return None;
+        }
+        let euclidean = self
+            .Vec
+            .iter()
+            .zip(&other.Vec)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f64>()
+            .sqrt();
+        let distance = euclidean + self.Height + other.Height;
+        let adjusted = distance + self.Adjustment + other.Adjustment;
+        let rtt = if adjusted > 0.0 { adjusted } else { distance };
+        Some(Duration::from_secs_f64(rtt.max(0.0)))
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct NodeCoordinate {
+    pub Node: String,
+    pub Segment: String,
+    pub Coord: NetworkCoordinate,
+}
+
+/// The WAN coordinates of the servers of one datacenter.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DatacenterCoordinate {
+    pub Datacenter: String,
+    pub AreaID: String,
+    pub Coordinates: Vec<NodeCoordinate>,
+}
+
+#[async_trait]
+pub trait Coordinate {
+    async fn datacenters(&self) -> Result<Vec<DatacenterCoordinate>>;
+    async fn nodes(&self, q: Option<&QueryOptions>) -> Result<(Vec<NodeCoordinate>, QueryMeta)>;
+}
+
+#[async_trait]
+impl Coordinate for Client {
+    /// https://www.consul.io/api/coordinate.html#read-wan-coordinates
+    async fn datacenters(&self) -> Result<Vec<DatacenterCoordinate>> {
+        get("/v1/coordinate/datacenters", &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/coordinate.html#read-lan-coordinates-for-all-nodes
+    async fn nodes(&self, q: Option<&QueryOptions>) -> Result<(Vec<NodeCoordinate>, QueryMeta)> {
+        get("/v1/coordinate/nodes", &self.config, Vec::new(), q).await
+    }
+}
@@ -0,0 +1,308 @@
+//! Serde helpers for the encodings Consul uses in its JSON payloads.
+
+/// (De)serializes a byte buffer as the base64 string Consul uses for binary
+/// values. A JSON `null` is read as an empty buffer.
+pub mod base64_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(encoded) => base64::decode(encoded).map_err(serde::de::Error::custom),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Like `base64_bytes`, keeping a JSON `null` apart as `None`.
+pub mod base64_bytes_opt {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        bytes: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => serializer.serialize_str(&base64::encode(bytes)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|encoded| base64::decode(encoded).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+/// The forms Consul writes durations in, see `GoDuration`: a Go duration
+/// string such as `"15s"` or `"1m30s"`, or an integer number of nanoseconds.
+pub mod go_duration {
+    use std::time::Duration;
+
+    use serde::de::{self, Deserializer, Visitor};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        deserializer.deserialize_any(GoDurationVisitor)
+    }
+
+    /// Formats `d` using the largest unit that represents it exactly.
+    pub fn format(d: Duration) -> String {
+        let nanos = d.as_nanos();
+        if nanos == 0 {
+            String::from("0s")
+        } else if nanos % 1_000_000_000 == 0 {
+            format!("{}s", nanos / 1_000_000_000)
+        } else if nanos % 1_000_000 == 0 {
+            format!("{}ms", nanos / 1_000_000)
+        } else if nanos % 1_000 == 0 {
+            format!("{}us", nanos / 1_000)
+        } else {
+            format!("{}ns", nanos)
+        }
+    }
+
+    /// Parses a Go duration string, e.g. `"300ms"`, `"1.5h"` or `"2h45m"`.
+    pub fn parse(s: &str) -> Option<Duration> {
+        if s.is_empty() {
+            return None;
+        }
+        if s == "0" {
+            return Some(Duration::from_secs(0));
+        }
+        let mut rest = s;
+        let mut nanos = 0f64;
+        while !rest.is_empty() {
+            let number_len = rest
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(rest.len());
+            let value: f64 = rest[..number_len].parse().ok()?;
+            rest = &rest[number_len..];
+            let unit_len = rest
+                .find(|c: char| c.is_ascii_digit() || c == '.')
+                .unwrap_or(rest.len());
+            let scale = match &rest[..unit_len] {
+                "ns" => 1f64,
+                "us" | "µs" => 1e3,
+                "ms" => 1e6,
+                "s" => 1e9,
+                "m" => 60e9,
+                "h" => 3600e9,
+                _ => return None,
+            };
+            rest = &rest[unit_len..];
+            nanos += value * scale;
+        }
+        Some(Duration::from_nanos(nanos.round() as u64))
+    }
+
+    struct GoDurationVisitor;
+
+    impl<'de> Visitor<'de> for GoDurationVisitor {
+        type Value = Duration;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a Go duration string or a number of nanoseconds")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(Duration::from_nanos(v))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            if v < 0 {
+                return Err(E::custom("negative durations are not supported"));
+            }
+            Ok(Duration::from_nanos(v as u64))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            parse(v).ok_or_else(|| E::custom(format!("invalid duration: {}", v)))
+        }
+    }
+}
+
+/// Decodes a gzip stream, the `Content-Encoding` Consul compresses responses
+/// with when asked to.
+pub mod gzip {
+    use std::io::{self, Read};
+
+    use flate2::read::MultiGzDecoder;
+
+    /// Reads every member of the stream, as gzip allows several, failing
+    /// once more than `limit` bytes come out.
+    pub fn decode(data: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+        let mut decoded = Vec::new();
+        MultiGzDecoder::new(data)
+            .take(limit as u64 + 1)
+            .read_to_end(&mut decoded)?;
+        if decoded.len() > limit {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("decompresses to more than {} bytes", limit),
+            ));
+        }
+        Ok(decoded)
+    }
+}
+
+/// Reads the RFC 3339 timestamps Consul writes, e.g.
+/// `"2021-01-09T22:58:56.16748Z"` or `"2020-05-22T11:04:46-04:00"`.
+pub mod rfc3339 {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// `None` for anything malformed, or before the Unix epoch.
+    pub fn parse(s: &str) -> Option<SystemTime> {
+        // Checked before slicing at byte offsets, which must fall on char
+        // boundaries
+        if !s.is_ascii() {
+            return None;
+        }
+        let b = s.as_bytes();
+        if b.len() < 20
+            || b[4] != b'-'
+            || b[7] != b'-'
+            || !matches!(b[10], b'T' | b't' | b' ')
+            || b[13] != b':'
+            || b[16] != b':'
+        {
+            return None;
+        }
+        let year = number(&s[0..4])?;
+        let month = number(&s[5..7])?;
+        let day = number(&s[8..10])?;
+        let hour = number(&s[11..13])?;
+        let minute = number(&s[14..16])?;
+        let second = number(&s[17..19])?;
+        if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+            return None;
+        }
+        if hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+
+        let mut rest = &s[19..];
+        let mut nanos = 0;
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let len = fraction
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(fraction.len());
+            if len == 0 {
+                return None;
+            }
+            // Digits past nanoseconds are dropped
+            let digits = &fraction[..len.min(9)];
+            nanos = number(digits)? * 10i64.pow(9 - digits.len() as u32);
+            rest = &fraction[len..];
+        }
+        let offset = match rest {
+            "Z" | "z" => 0,
+            _ if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+                let offset = number(&rest[1..3])? * 3600 + number(&rest[4..6])? * 60;
+                match rest.as_bytes()[0] {
+                    b'+' => offset,
+                    b'-' => -offset,
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+
+        let seconds =
+            days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset;
+        if seconds < 0 {
+            return None;
+        }
+        Some(UNIX_EPOCH + Duration::new(seconds as u64, nanos as u32))
+    }
+
+    fn number(s: &str) -> Option<i64> {
+        if s.bytes().all(|b| b.is_ascii_digit()) {
+            s.parse().ok()
+        } else {
+            None
+        }
+    }
+
+    fn days_in_month(year: i64, month: i64) -> i64 {
+        match month {
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    /// Days since 1970-01-01 in the proleptic Gregorian calendar.
+    fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gzip, rfc3339};
+    use std::io::Write;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn gzip_decode_limit() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(&[0; 4096]).unwrap();
+        let data = encoder.finish().unwrap();
+        assert_eq!(gzip::decode(&data, 4096).unwrap(), [0; 4096]);
+        assert!(gzip::decode(&data, 4095).is_err());
+    }
+
+    #[test]
+    fn rfc3339_fractions_and_offsets() {
+        // 2021-03-01T05:00:00Z
+        let at = |nanos| Some(UNIX_EPOCH + Duration::new(1_614_574_800, nanos));
+        assert_eq!(rfc3339::parse("2021-03-01T05:00:00Z"), at(0));
+        assert_eq!(rfc3339::parse("2021-03-01t05:00:00z"), at(0));
+        assert_eq!(
+            rfc3339::parse("2021-03-01T00:00:00.123456789-05:00"),
+            at(123_456_789)
+        );
+        assert_eq!(
+            rfc3339::parse("2021-03-01T10:30:00.5+05:30"),
+            at(500_000_000)
+        );
+        // Digits past nanoseconds are dropped rather than rounded
+        assert_eq!(
+            rfc3339::parse("2021-03-01T05:00:00.1234567899Z"),
+            at(123_456_789)
+        );
+        // Across a day boundary
+        assert_eq!(rfc3339::parse("2021-02-28T23:00:00-06:00"), at(0));
+        // February 29th of leap years only
+        assert!(rfc3339::parse("2020-02-29T00:00:00Z").is_some());
+        assert!(rfc3339::parse("2000-02-29T00:00:00Z").is_some());
+        assert_eq!(rfc3339::parse("2100-02-29T00:00:00Z"), None);
+
+        for malformed in [
+            "2021-03-01T05:00:00",
+            "2021-03-01T05:00:00.Z",
+            "2021-03-01T05:00:00+0500",
+            "2021-03-01T05:00:00*05:00",
+            "2021-13-01T05:00:00Z",
+            "2021-02-29T05:00:00Z",
+            "2021-04-31T05:00:00Z",
+            "1969-12-31T23:59:59Z",
+            "2021-01-09T22:58:5€xxxx",
+            "2021-01-09T22:58:50.€Z",
+        ] {
+            assert_eq!(rfc3339::parse(malformed), None, "{}", malformed);
+        }
+    }
+}
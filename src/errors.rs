@@ -6,6 +6,42 @@ error_chain! {
         ConsulError{
             description("")
         }
+        Consul(status: u16, body: String) {
+            description("consul returned an error response")
+            display("consul returned HTTP {}: {}", status, body)
+        }
+        AlreadyBootstrapped {
+            description("the ACL system has already been bootstrapped")
+            display("the ACL system has already been bootstrapped")
+        }
+        /// Consul has no leader: its servers are starting, or lost quorum.
+        /// Transient, and retried under `Config::retry` like any 5xx.
+        NoClusterLeader {
+            description("consul has no cluster leader")
+            display("consul has no cluster leader")
+        }
+        /// The session a lock or semaphore was held on was invalidated, or
+        /// could not be renewed before its TTL ran out.
+        SessionLost(session: String, cause: String) {
+            description("the session was lost")
+            display("session {} was lost: {}", session, cause)
+        }
+        Timeout {
+            description("request to consul timed out")
+            display("request to consul timed out")
+        }
+        EnterpriseOnly(feature: String) {
+            description("the feature requires Consul Enterprise")
+            display("{} require Consul Enterprise", feature)
+        }
+        PayloadTooLarge(limit: usize, actual: usize) {
+            description("event payload too large")
+            display("event payload of {} bytes exceeds the limit of {} bytes", actual, limit)
+        }
+        InvalidCheckState(state: String) {
+            description("invalid health check state")
+            display("invalid health check state '{}', expected one of any, passing, warning or critical", state)
+        }
     }
 
 }
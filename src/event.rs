@@ -0,0 +1,102 @@
+use async_trait::async_trait;
+
+use crate::errors::{ErrorKind, Result};
+use crate::request::{get_vec, put_bytes};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+
+/// The largest event payload stock Consul agents accept.
+pub const MAX_PAYLOAD_SIZE: usize = 512;
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct UserEvent {
+    pub ID: String,
+    pub Name: String,
+    #[serde(with = "crate::encoding::base64_bytes")]
+    pub Payload: Vec<u8>,
+    /// Regular expression on node names, empty for all nodes.
+    pub NodeFilter: String,
+    /// Regular expression on service names, empty for all services.
+    pub ServiceFilter: String,
+    /// Regular expression on service tags, only applied along with
+    /// `ServiceFilter`.
+    pub TagFilter: String,
+    pub Version: u64,
+    /// Lamport time of the event in the gossip pool.
+    pub LTime: u64,
+}
+
+/// Restricts which agents act on a fired event. Each filter is a regular
+/// expression, and `tag` is only honored together with `service`.
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    pub node: Option<String>,
+    pub service: Option<String>,
+    pub tag: Option<String>,
+}
+
+#[async_trait]
+pub trait Event {
+    async fn fire(
+        &self,
+        name: &str,
+        payload: &[u8],
+        filter: Option<&EventFilter>,
+        options: Option<&WriteOptions>,
+    ) -> Result<(UserEvent, WriteMeta)>;
+    async fn list(
+        &self,
+        name: Option<&str>,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<UserEvent>, QueryMeta)>;
+}
+
+#[async_trait]
+impl Event for Client {
+    /// https://www.consul.io/api/event.html#fire-event
+    ///
+    /// A payload larger than `Config::max_event_payload` fails with
+    /// `ErrorKind::PayloadTooLarge`, without contacting Consul.
+    async fn fire(
+        &self,
+        name: &str,
+        payload: &[u8],
+        filter: Option<&EventFilter>,
+        options: Option<&WriteOptions>,
+    ) -> Result<(UserEvent, WriteMeta)> {
+        let limit = self.config.max_event_payload;
+        if payload.len() > limit {
+            return Err(ErrorKind::PayloadTooLarge(limit, payload.len()).into());
+        }
+        let mut params = Vec::new();
+        if let Some(filter) = filter {
+            if let Some(node) = &filter.node {
+                params.push((String::from("node"), node.to_owned()));
+            }
+            if let Some(service) = &filter.service {
+                params.push((String::from("service"), service.to_owned()));
+            }
+            if let Some(tag) = &filter.tag {
+                params.push((String::from("tag"), tag.to_owned()));
+            }
+        }
+        let path = format!("/v1/event/fire/{}", name);
+        put_bytes(&path, payload, &self.config, params, options).await
+    }
+
+    /// https://www.consul.io/api/event.html#list-events
+    ///
+    /// Only the most recent events the agent has seen are kept, so older
+    /// events may be missing from the list.
+    async fn list(
+        &self,
+        name: Option<&str>,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<UserEvent>, QueryMeta)> {
+        let mut params = Vec::new();
+        if let Some(name) = name {
+            params.push((String::from("name"), name.to_owned()));
+        }
+        get_vec("/v1/event/list", &self.config, params, options).await
+    }
+}
@@ -1,26 +1,29 @@
 use async_trait::async_trait;
 use std::collections::HashMap;
 
-use crate::agent::AgentService;
-use crate::errors::Result;
+use crate::agent::{AgentService, CheckStatus};
+use crate::errors::{ErrorKind, Result};
 use crate::request::get;
 use crate::{Client, QueryMeta, QueryOptions};
 
-#[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct HealthCheck {
     pub Node: String,
     pub CheckID: String,
     pub Name: String,
-    pub Status: String,
+    pub Status: CheckStatus,
     pub Notes: String,
     pub Output: String,
     pub ServiceID: String,
     pub ServiceName: String,
     pub ServiceTags: Option<Vec<String>>,
+    pub CreateIndex: u64,
+    /// Bumped whenever the check's status or output changes.
+    pub ModifyIndex: u64,
 }
 
-#[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct Node {
     pub ID: String,
@@ -33,7 +36,7 @@ pub struct Node {
     pub ModifyIndex: u64,
 }
 
-#[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct ServiceEntry {
     pub Node: Node,
@@ -43,6 +46,16 @@ pub struct ServiceEntry {
 
 #[async_trait]
 pub trait Health {
+    async fn checks(
+        &self,
+        service: &str,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<HealthCheck>, QueryMeta)>;
+    async fn node(
+        &self,
+        node: &str,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<HealthCheck>, QueryMeta)>;
     async fn service(
         &self,
         service: &str,
@@ -50,10 +63,51 @@ pub trait Health {
         passing_only: bool,
         options: Option<&QueryOptions>,
     ) -> Result<(Vec<ServiceEntry>, QueryMeta)>;
+    async fn service_with_tags(
+        &self,
+        service: &str,
+        tags: &[&str],
+        passing_only: bool,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<ServiceEntry>, QueryMeta)>;
+    async fn connect(
+        &self,
+        service: &str,
+        passing_only: bool,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<ServiceEntry>, QueryMeta)>;
+    async fn state(
+        &self,
+        state: &str,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<HealthCheck>, QueryMeta)>;
 }
 
 #[async_trait]
 impl Health for Client {
+    /// https://www.consul.io/api/health.html#list-checks-for-service
+    async fn checks(
+        &self,
+        service: &str,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<HealthCheck>, QueryMeta)> {
+        let path = format!("/v1/health/checks/{}", service);
+        get(&path, &self.config, Vec::new(), options).await
+    }
+
+    /// https://www.consul.io/api/health.html#list-checks-for-node
+    ///
+    /// A node without any registered checks yields an empty list.
+    async fn node(
+        &self,
+        node: &str,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<HealthCheck>, QueryMeta)> {
+        let path = format!("/v1/health/node/{}", node);
+        get(&path, &self.config, Vec::new(), options).await
+    }
+
+    /// https://www.consul.io/api/health.html#list-nodes-for-service
     async fn service(
         &self,
         service: &str,
@@ -61,14 +115,65 @@ impl Health for Client {
         passing_only: bool,
         options: Option<&QueryOptions>,
     ) -> Result<(Vec<ServiceEntry>, QueryMeta)> {
-        let mut params = HashMap::new();
+        let tags: Vec<&str> = tag.into_iter().collect();
+        self.service_with_tags(service, &tags, passing_only, options)
+            .await
+    }
+
+    /// https://www.consul.io/api/health.html#list-nodes-for-service
+    ///
+    /// Only instances carrying every one of `tags` are returned.
+    async fn service_with_tags(
+        &self,
+        service: &str,
+        tags: &[&str],
+        passing_only: bool,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<ServiceEntry>, QueryMeta)> {
+        let mut params = Vec::new();
         let path = format!("/v1/health/service/{}", service);
         if passing_only {
-            params.insert(String::from("passing"), String::from("1"));
+            params.push((String::from("passing"), String::from("1")));
         }
-        if let Some(tag) = tag {
-            params.insert(String::from("tag"), tag.to_owned());
+        for tag in tags {
+            params.push((String::from("tag"), String::from(*tag)));
         }
         get(&path, &self.config, params, options).await
     }
+
+    /// https://www.consul.io/api/health.html#list-nodes-for-connect-capable-service
+    ///
+    /// Like `service`, but only the instances reachable over Connect: the
+    /// sidecar proxies of `service` and its Connect-native instances.
+    async fn connect(
+        &self,
+        service: &str,
+        passing_only: bool,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<ServiceEntry>, QueryMeta)> {
+        let mut params = Vec::new();
+        let path = format!("/v1/health/connect/{}", service);
+        if passing_only {
+            params.push((String::from("passing"), String::from("1")));
+        }
+        get(&path, &self.config, params, options).await
+    }
+
+    /// https://www.consul.io/api/health.html#list-checks-in-state
+    ///
+    /// `state` must be one of `any`, `passing`, `warning` or `critical`;
+    /// anything else fails with `ErrorKind::InvalidCheckState` without
+    /// contacting Consul.
+    async fn state(
+        &self,
+        state: &str,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<HealthCheck>, QueryMeta)> {
+        match state {
+            "any" | "passing" | "warning" | "critical" => {}
+            _ => return Err(ErrorKind::InvalidCheckState(state.to_owned()).into()),
+        }
+        let path = format!("/v1/health/state/{}", state);
+        get(&path, &self.config, Vec::new(), options).await
+    }
 }
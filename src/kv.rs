@@ -1,10 +1,14 @@
 use std::collections::HashMap;
+use std::string::FromUtf8Error;
+use std::time::Instant;
 
 use crate::errors::Error;
 use crate::errors::Result;
-use crate::request::{delete, get, get_vec, put};
+use crate::request::{delete, get_vec, get_with_status, put_bytes};
 use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use reqwest::StatusCode;
 
 #[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
@@ -15,87 +19,245 @@ pub struct KVPair {
     pub ModifyIndex: Option<u64>,
     pub LockIndex: Option<u64>,
     pub Flags: Option<u64>,
-    pub Value: String,
+    /// The raw bytes Consul stores, `None` for keys without a value, such as
+    /// the folders `list` returns. See `value_string` for text values.
+    #[serde(with = "crate::encoding::base64_bytes_opt")]
+    pub Value: Option<Vec<u8>>,
     pub Session: Option<String>,
+    /// Set by Consul Enterprise, see `Config::namespace`.
+    #[serde(skip_serializing)]
+    pub Namespace: Option<String>,
+}
+
+fn flags_param(pair: &KVPair) -> Vec<(String, String)> {
+    match pair.Flags {
+        Some(flags) => vec![(String::from("flags"), flags.to_string())],
+        None => Vec::new(),
+    }
+}
+
+impl KVPair {
+    /// The value as UTF-8 text, `None` when the key has no value.
+    pub fn value_string(&self) -> Option<std::result::Result<String, FromUtf8Error>> {
+        self.Value.clone().map(String::from_utf8)
+    }
+
+    fn value(&self) -> &[u8] {
+        self.Value.as_deref().unwrap_or_default()
+    }
 }
 
 #[allow(clippy::upper_case_acronyms)]
 #[async_trait]
 pub trait KV {
     async fn acquire(&self, _: &KVPair, _: Option<&WriteOptions>) -> Result<(bool, WriteMeta)>;
+    async fn cas(&self, _: &KVPair, _: u64, _: Option<&WriteOptions>) -> Result<(bool, WriteMeta)>;
     async fn delete(&self, _: &str, _: Option<&WriteOptions>) -> Result<(bool, WriteMeta)>;
     async fn get(&self, _: &str, _: Option<&QueryOptions>) -> Result<(Option<KVPair>, QueryMeta)>;
+    async fn get_many(
+        &self,
+        _: &[&str],
+        _: Option<&QueryOptions>,
+    ) -> HashMap<String, Result<Option<KVPair>>>;
+    async fn keys(
+        &self,
+        _: &str,
+        _: Option<&str>,
+        _: Option<&QueryOptions>,
+    ) -> Result<(Vec<String>, QueryMeta)>;
     async fn list(&self, _: &str, _: Option<&QueryOptions>) -> Result<(Vec<KVPair>, QueryMeta)>;
     async fn put(&self, _: &KVPair, _: Option<&WriteOptions>) -> Result<(bool, WriteMeta)>;
+    async fn put_flags(
+        &self,
+        _: &str,
+        _: u64,
+        _: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)>;
     async fn release(&self, _: &KVPair, _: Option<&WriteOptions>) -> Result<(bool, WriteMeta)>;
 }
 
 #[async_trait]
 impl KV for Client {
+    /// https://www.consul.io/api/kv.html#acquire
+    ///
+    /// Takes the lock on `pair.Key` for `pair.Session`, writing `pair.Value`.
+    /// Acquiring a key already held by another session is not an error: the
+    /// returned flag is `false`. The write takes a whole `KVPair` like `put`,
+    /// so that it can set `pair.Flags` too.
     async fn acquire(&self, pair: &KVPair, o: Option<&WriteOptions>) -> Result<(bool, WriteMeta)> {
-        let mut params = HashMap::new();
-        if let Some(i) = pair.Flags {
-            if i != 0 {
-                params.insert(String::from("flags"), i.to_string());
-            }
-        }
+        let mut params = flags_param(pair);
         if let Some(ref session) = pair.Session {
-            params.insert(String::from("acquire"), session.to_owned());
+            params.push((String::from("acquire"), session.to_owned()));
             let path = format!("/v1/kv/{}", pair.Key);
-            put(&path, Some(&pair.Value), &self.config, params, o).await
+            put_bytes(&path, pair.value(), &self.config, params, o).await
         } else {
             Err(Error::from("Session flag is required to acquire lock"))
         }
     }
 
+    /// https://www.consul.io/api/kv.html#cas
+    ///
+    /// Writes `pair` only if the key's `ModifyIndex` still equals `index`
+    /// (an index of 0 only creates the key if it does not exist). Consul
+    /// answers a rejected write with HTTP 200 and a `false` body, so the
+    /// returned flag is the only way to tell whether the write happened.
+    async fn cas(
+        &self,
+        pair: &KVPair,
+        index: u64,
+        o: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)> {
+        let mut params = flags_param(pair);
+        params.push((String::from("cas"), index.to_string()));
+        let path = format!("/v1/kv/{}", pair.Key);
+        put_bytes(&path, pair.value(), &self.config, params, o).await
+    }
+
     async fn delete(&self, key: &str, options: Option<&WriteOptions>) -> Result<(bool, WriteMeta)> {
         let path = format!("/v1/kv/{}", key);
-        delete(&path, &self.config, HashMap::new(), options).await
+        delete(
+            &path,
+            None as Option<&()>,
+            &self.config,
+            Vec::new(),
+            options,
+        )
+        .await
     }
+    /// https://www.consul.io/api/kv.html#read-key
+    ///
+    /// A missing key is `Ok(None)`; any other failure, such as a 403 for a
+    /// token without read access, is an error.
     async fn get(
         &self,
         key: &str,
         options: Option<&QueryOptions>,
     ) -> Result<(Option<KVPair>, QueryMeta)> {
         let path = format!("/v1/kv/{}", key);
-        let x: Result<(Vec<KVPair>, QueryMeta)> =
-            get(&path, &self.config, HashMap::new(), options).await;
-        x.map(|r| (r.0.first().cloned(), r.1))
+        let (pairs, _, meta): (Option<Vec<KVPair>>, _, _) = get_with_status(
+            &path,
+            &self.config,
+            Vec::new(),
+            options,
+            &[StatusCode::NOT_FOUND],
+        )
+        .await?;
+        Ok((pairs.and_then(|p| p.into_iter().next()), meta))
+    }
+
+    /// Reads each of `keys`, `Config::batch_concurrency` of them at a time.
+    /// Every key gets an entry, the outcome of its own `get`: a failed read
+    /// does not fail the others.
+    async fn get_many(
+        &self,
+        keys: &[&str],
+        options: Option<&QueryOptions>,
+    ) -> HashMap<String, Result<Option<KVPair>>> {
+        let keys: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+        let reads = keys.into_iter().map(|key| async move {
+            let pair = self.get(&key, options).await.map(|(pair, _)| pair);
+            (key, pair)
+        });
+        // No reads at all would ever start with 0
+        stream::iter(reads)
+            .buffer_unordered(self.config.batch_concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// https://www.consul.io/api/kv.html#keys
+    ///
+    /// Returns only the key names under `prefix`, optionally collapsed up to
+    /// the next `separator`. A missing prefix yields an empty list.
+    async fn keys(
+        &self,
+        prefix: &str,
+        separator: Option<&str>,
+        o: Option<&QueryOptions>,
+    ) -> Result<(Vec<String>, QueryMeta)> {
+        let mut params = Vec::new();
+        params.push((String::from("keys"), String::from("")));
+        if let Some(separator) = separator {
+            params.push((String::from("separator"), separator.to_owned()));
+        }
+        let path = format!("/v1/kv/{}", prefix);
+        get_vec(&path, &self.config, params, o).await
     }
 
+    /// https://www.consul.io/api/kv.html#recurse
+    ///
+    /// Returns every pair under `prefix`; an empty prefix lists the whole
+    /// store. A missing prefix yields an empty list.
     async fn list(
         &self,
         prefix: &str,
         o: Option<&QueryOptions>,
     ) -> Result<(Vec<KVPair>, QueryMeta)> {
-        let mut params = HashMap::new();
-        params.insert(String::from("recurse"), String::from(""));
+        let params = vec![(String::from("recurse"), String::from(""))];
         let path = format!("/v1/kv/{}", prefix);
         get_vec(&path, &self.config, params, o).await
     }
 
+    /// https://www.consul.io/api/kv.html#create-update-key
+    ///
+    /// Replaces both the value and, when `pair.Flags` is set, the flags; a
+    /// `None` value writes an empty one. See `put_flags` to only change the
+    /// flags.
     async fn put(&self, pair: &KVPair, o: Option<&WriteOptions>) -> Result<(bool, WriteMeta)> {
-        let mut params = HashMap::new();
-        if let Some(i) = pair.Flags {
-            if i != 0 {
-                params.insert(String::from("flags"), i.to_string());
-            }
-        }
+        let params = flags_param(pair);
         let path = format!("/v1/kv/{}", pair.Key);
-        put(&path, Some(&pair.Value), &self.config, params, o).await
+        put_bytes(&path, pair.value(), &self.config, params, o).await
     }
 
-    async fn release(&self, pair: &KVPair, o: Option<&WriteOptions>) -> Result<(bool, WriteMeta)> {
-        let mut params = HashMap::new();
-        if let Some(i) = pair.Flags {
-            if i != 0 {
-                params.insert(String::from("flags"), i.to_string());
+    /// Sets the flags of `key`, keeping its value. Consul stores a request's
+    /// body as the value, so this reads the pair and writes it back with a
+    /// `cas` on its index: `false` when the key does not exist or changed in
+    /// between.
+    async fn put_flags(
+        &self,
+        key: &str,
+        flags: u64,
+        o: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)> {
+        let start = Instant::now();
+        // Read from where the write goes
+        let q = o.map(|o| QueryOptions {
+            datacenter: o.datacenter.clone(),
+            namespace: o.namespace.clone(),
+            partition: o.partition.clone(),
+            token: o.token.clone(),
+            ..Default::default()
+        });
+        let (current, _) = self.get(key, q.as_ref()).await?;
+        let mut pair = match current {
+            Some(pair) => pair,
+            None => {
+                return Ok((
+                    false,
+                    WriteMeta {
+                        last_index: None,
+                        request_time: Instant::now() - start,
+                    },
+                ))
             }
-        }
+        };
+        pair.Flags = Some(flags);
+        let index = pair.ModifyIndex.unwrap_or_default();
+        self.cas(&pair, index, o).await
+    }
+
+    /// https://www.consul.io/api/kv.html#release
+    ///
+    /// Releases the lock `pair.Session` holds on `pair.Key`. Consul stores the
+    /// request body as the new value, so `pair.Value` must carry the value to
+    /// keep, which a signature with only the key and the session could not.
+    /// Releasing a key this session does not hold returns `false`.
+    async fn release(&self, pair: &KVPair, o: Option<&WriteOptions>) -> Result<(bool, WriteMeta)> {
+        let mut params = flags_param(pair);
         if let Some(ref session) = pair.Session {
-            params.insert(String::from("release"), session.to_owned());
+            params.push((String::from("release"), session.to_owned()));
             let path = format!("/v1/kv/{}", pair.Key);
-            put(&path, Some(&pair.Value), &self.config, params, o).await
+            put_bytes(&path, pair.value(), &self.config, params, o).await
         } else {
             Err(Error::from("Session flag is required to release a lock"))
         }
@@ -0,0 +1,285 @@
+//! Leader election over a session-held KV lock.
+//!
+//! https://learn.hashicorp.com/tutorials/consul/application-leader-elections
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, BoxStream, StreamExt};
+use reqwest::StatusCode;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::encoding::go_duration;
+use crate::errors::{ErrorKind, Result};
+use crate::kv::{KVPair, KV};
+use crate::request::get_with_status;
+use crate::session::{Session, SessionEntry};
+use crate::watch::next_index;
+use crate::{Client, QueryOptions};
+
+/// Campaigns for the lock on a key in the background, for as long as it
+/// lives.
+///
+/// The election runs on a session with the given TTL, renewed at half of
+/// it. Whenever the session is lost, e.g. invalidated by an operator or
+/// left to expire while Consul was unreachable, the elector reports the
+/// loss of leadership and campaigns again on a new session.
+///
+/// Dropping the elector stops the campaign but leaves its session to
+/// expire, delaying the next election by up to the TTL; `resign` hands the
+/// lock over right away.
+pub struct LeaderElector {
+    client: Client,
+    key: String,
+    value: Vec<u8>,
+    shared: Arc<Shared>,
+    task: JoinHandle<()>,
+}
+
+struct Shared {
+    leader: AtomicBool,
+    session: Mutex<String>,
+    events: broadcast::Sender<bool>,
+}
+
+impl Shared {
+    fn set_leader(&self, leader: bool) {
+        if self.leader.swap(leader, Ordering::SeqCst) != leader {
+            // No subscriber is not an error
+            let _ = self.events.send(leader);
+        }
+    }
+}
+
+impl LeaderElector {
+    /// Creates the session and starts campaigning for `key`, storing `value`
+    /// in it, typically an identifier of this instance, while leading.
+    ///
+    /// Consul requires `ttl` to be between 10 seconds and 24 hours.
+    pub async fn start(
+        client: &Client,
+        key: &str,
+        value: &[u8],
+        ttl: Duration,
+    ) -> Result<LeaderElector> {
+        let session = create_session(client, key, ttl).await?;
+        let (events, _) = broadcast::channel(16);
+        let shared = Arc::new(Shared {
+            leader: AtomicBool::new(false),
+            session: Mutex::new(session),
+            events,
+        });
+        let task = tokio::spawn(campaign(
+            client.clone(),
+            key.to_owned(),
+            value.to_vec(),
+            ttl,
+            shared.clone(),
+        ));
+        Ok(LeaderElector {
+            client: client.clone(),
+            key: key.to_owned(),
+            value: value.to_vec(),
+            shared,
+            task,
+        })
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.shared.leader.load(Ordering::SeqCst)
+    }
+
+    /// The ID of the session currently campaigning.
+    pub fn session(&self) -> String {
+        self.shared.session.lock().unwrap().clone()
+    }
+
+    /// Yields whether this elector leads, then `true` each time it becomes
+    /// the leader and `false` each time it loses leadership. A subscriber
+    /// far behind skips the oldest changes.
+    pub fn changes(&self) -> BoxStream<'static, bool> {
+        let events = self.shared.events.subscribe();
+        // Read after subscribing, so that no change is missed in between;
+        // one already queued as well is skipped as a repeat
+        let current = self.is_leader();
+        let state = (events, Some(current), None);
+        stream::unfold(state, |(mut events, mut pending, mut last)| async move {
+            loop {
+                let leader = match pending.take() {
+                    Some(leader) => leader,
+                    None => match events.recv().await {
+                        Ok(leader) => leader,
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    },
+                };
+                if last != Some(leader) {
+                    last = Some(leader);
+                    return Some((leader, (events, None, last)));
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// Stops campaigning, releasing the lock if held, and destroys the
+    /// session.
+    pub async fn resign(self) -> Result<()> {
+        self.task.abort();
+        let session = self.session();
+        let released = if self.is_leader() {
+            // Unlike destroying the session, a release does not hold the
+            // other contenders back for the session's lock-delay
+            let pair = KVPair {
+                Key: self.key.clone(),
+                Value: Some(self.value.clone()),
+                Session: Some(session.clone()),
+                ..Default::default()
+            };
+            self.client.release(&pair, None).await.map(|_| ())
+        } else {
+            Ok(())
+        };
+        // Destroyed even if the release failed, which frees the lock too
+        let destroyed = self.client.destroy(&session, None).await.map(|_| ());
+        if released.is_ok() || destroyed.is_ok() {
+            self.shared.set_leader(false);
+        }
+        released.and(destroyed)
+    }
+}
+
+impl Drop for LeaderElector {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn create_session(client: &Client, key: &str, ttl: Duration) -> Result<String> {
+    let entry = SessionEntry {
+        Name: Some(format!("leader election on {}", key)),
+        TTL: Some(go_duration::format(ttl)),
+        Behavior: Some(String::from("release")),
+        ..Default::default()
+    };
+    client.create(&entry, None).await.map(|(id, _)| id.ID)
+}
+
+/// Campaigns on a new session, the previous one having gone with the lock.
+async fn replace_session(
+    client: &Client,
+    key: &str,
+    ttl: Duration,
+    shared: &Shared,
+) -> Result<String> {
+    shared.set_leader(false);
+    let id = create_session(client, key, ttl).await?;
+    *shared.session.lock().unwrap() = id.clone();
+    Ok(id)
+}
+
+/// Renews the session, tries to take the lock while not leading, and
+/// otherwise blocks on the key until either it changes or a renewal is due.
+async fn campaign(client: Client, key: String, value: Vec<u8>, ttl: Duration, shared: Arc<Shared>) {
+    let path = format!("/v1/kv/{}", key);
+    let renew_every = ttl / 2;
+    let mut renewed = Instant::now();
+    let mut index = None;
+    loop {
+        let mut session = shared.session.lock().unwrap().clone();
+        if renewed.elapsed() >= renew_every {
+            match client.renew(&session, None).await {
+                Ok(_) => renewed = Instant::now(),
+                Err(e) => match e.kind() {
+                    ErrorKind::Consul(404, _) => {
+                        // Invalidated, the lock went with it
+                        match replace_session(&client, &key, ttl, &shared).await {
+                            Ok(id) => {
+                                session = id;
+                                renewed = Instant::now();
+                            }
+                            Err(_) => {
+                                tokio::time::sleep(retry_delay(ttl)).await;
+                                continue;
+                            }
+                        }
+                    }
+                    _ => {
+                        // Consul will have expired the session by now
+                        if renewed.elapsed() >= ttl {
+                            shared.set_leader(false);
+                        }
+                        tokio::time::sleep(retry_delay(ttl)).await;
+                        continue;
+                    }
+                },
+            }
+        }
+
+        if !shared.leader.load(Ordering::SeqCst) {
+            let pair = KVPair {
+                Key: key.clone(),
+                Value: Some(value.clone()),
+                Session: Some(session.clone()),
+                ..Default::default()
+            };
+            match client.acquire(&pair, None).await {
+                Ok((true, _)) => shared.set_leader(true),
+                Ok((false, _)) => {}
+                Err(_) => {
+                    // Consul refuses the lock to a session it no longer
+                    // knows, which may have gone since the last renewal
+                    let gone = matches!(
+                        client.info(&session, None).await,
+                        Ok((entries, _)) if entries.is_empty()
+                    );
+                    if gone && replace_session(&client, &key, ttl, &shared).await.is_ok() {
+                        renewed = Instant::now();
+                        continue;
+                    }
+                    tokio::time::sleep(retry_delay(ttl)).await;
+                    continue;
+                }
+            }
+        }
+
+        let options = QueryOptions {
+            wait_index: index,
+            wait_time: Some(
+                renew_every
+                    .saturating_sub(renewed.elapsed())
+                    .max(Duration::from_secs(1)),
+            ),
+            ..Default::default()
+        };
+        let answer: Result<(Option<Vec<KVPair>>, _, _)> = get_with_status(
+            &path,
+            &client.config,
+            Vec::new(),
+            Some(&options),
+            &[StatusCode::NOT_FOUND],
+        )
+        .await;
+        match answer {
+            Ok((pairs, _, meta)) => {
+                let holder = pairs
+                    .and_then(|p| p.into_iter().next())
+                    .and_then(|pair| pair.Session);
+                if holder.as_deref() != Some(session.as_str()) {
+                    shared.set_leader(false);
+                }
+                index = Some(next_index(index, &meta));
+            }
+            Err(_) => {
+                index = None;
+                tokio::time::sleep(retry_delay(ttl)).await;
+            }
+        }
+    }
+}
+
+fn retry_delay(ttl: Duration) -> Duration {
+    (ttl / 10).min(Duration::from_secs(1))
+}
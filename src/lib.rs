@@ -6,24 +6,52 @@ extern crate error_chain;
 #[macro_use]
 extern crate serde_derive;
 
+pub mod acl;
 pub mod agent;
 pub mod catalog;
+pub mod config_entry;
 pub mod connect_ca;
+pub mod connect_intentions;
+pub mod coordinate;
 pub mod errors;
+pub mod event;
 pub mod health;
 pub mod kv;
+pub mod leader;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod operator;
+pub mod query;
+pub mod semaphore;
 pub mod session;
+pub mod snapshot;
+pub mod status;
+pub mod tls;
+pub mod txn;
+pub mod watch;
 
+mod encoding;
 mod request;
 
+use std::collections::HashMap;
 use std::env;
+use std::fmt;
+use std::path::PathBuf;
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
 
 use std::time::Duration;
 
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
 use reqwest::Client as HttpClient;
-use reqwest::ClientBuilder;
+use reqwest::ClientBuilder as HttpClientBuilder;
+use url::Url;
 
-use errors::{Result, ResultExt};
+use errors::{Error, Result, ResultExt};
+#[cfg(feature = "metrics")]
+use metrics::RequestObserver;
+use tls::TlsConfig;
 
 #[derive(Clone, Debug)]
 pub struct Client {
@@ -34,53 +62,417 @@ impl Client {
     pub fn new(config: Config) -> Self {
         Client { config }
     }
+
+    /// Reads an endpoint this crate does not model yet, e.g.
+    /// `client.get_raw("/v1/agent/version", &[], None)`, returning the body
+    /// as is along with all the response headers. The request goes through
+    /// the same token, datacenter, retry and error handling as the rest of
+    /// the client.
+    pub async fn get_raw(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+        q: Option<&QueryOptions>,
+    ) -> Result<(Bytes, HeaderMap)> {
+        let params = params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        request::get_raw(path, &self.config, params, q).await
+    }
 }
 
-#[derive(Clone, Debug)]
+/// Builds a `Client` without assembling a `Config` by hand:
+///
+/// ```no_run
+/// # fn main() -> consul::errors::Result<()> {
+/// let client = consul::ClientBuilder::new()
+///     .address("http://localhost:8500")
+///     .datacenter("dc1")
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct ClientBuilder {
+    address: Option<String>,
+    datacenter: Option<String>,
+    token: Option<String>,
+    timeout: Option<Duration>,
+    retry: Option<RetryPolicy>,
+    tls: Option<TlsConfig>,
+    namespace: Option<String>,
+    partition: Option<String>,
+    http_client: Option<HttpClient>,
+    transport: Transport,
+    gzip: bool,
+    batch_concurrency: Option<usize>,
+    max_event_payload: Option<usize>,
+    observer: Observer,
+}
+
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("address", &self.address)
+            .field("datacenter", &self.datacenter)
+            .field("token", &redact(&self.token))
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .field("tls", &self.tls)
+            .field("namespace", &self.namespace)
+            .field("partition", &self.partition)
+            .field("http_client", &self.http_client)
+            .field("transport", &self.transport)
+            .field("gzip", &self.gzip)
+            .field("batch_concurrency", &self.batch_concurrency)
+            .field("max_event_payload", &self.max_event_payload)
+            .field("observer", &self.observer)
+            .finish()
+    }
+}
+
+/// Connection settings of the HTTP client built by `ClientBuilder`, reqwest's
+/// defaults where unset.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Transport {
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+}
+
+impl Transport {
+    fn apply(&self, mut builder: HttpClientBuilder) -> HttpClientBuilder {
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(interval) = self.tcp_keepalive {
+            builder = builder.tcp_keepalive(interval);
+        }
+        builder
+    }
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        ClientBuilder::default()
+    }
+
+    /// Starts from the environment variables honored by the `consul` CLI:
+    /// `CONSUL_HTTP_ADDR` (defaulting to `127.0.0.1:8500`), `CONSUL_HTTP_TOKEN`,
+    /// `CONSUL_HTTP_SSL`, which selects `https` for an address given without
+    /// a scheme, and for an `https` address `CONSUL_CACERT` along with
+    /// `CONSUL_CLIENT_CERT` and `CONSUL_CLIENT_KEY`, which go together.
+    pub fn from_env() -> Self {
+        let address = address_from_env();
+        let tls = if address.starts_with("https://") {
+            Some(tls_from_env())
+        } else {
+            None
+        };
+        ClientBuilder {
+            address: Some(address),
+            token: env::var("CONSUL_HTTP_TOKEN").ok(),
+            tls,
+            ..ClientBuilder::default()
+        }
+    }
+
+    /// The agent's HTTP address, `http://localhost:8500` by default.
+    pub fn address(mut self, address: &str) -> Self {
+        self.address = Some(address.to_owned());
+        self
+    }
+
+    /// The default datacenter, which `QueryOptions`/`WriteOptions` can
+    /// override per request.
+    pub fn datacenter(mut self, datacenter: impl Into<Datacenter>) -> Self {
+        self.datacenter = Some(datacenter.into().0);
+        self
+    }
+
+    /// The default Consul Enterprise namespace, which `QueryOptions`/
+    /// `WriteOptions` can override per request.
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.to_owned());
+        self
+    }
+
+    /// The default Consul Enterprise admin partition, see `Config::partition`.
+    pub fn partition(mut self, partition: &str) -> Self {
+        self.partition = Some(partition.to_owned());
+        self
+    }
+
+    /// The ACL token sent with every request.
+    pub fn token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_owned());
+        self
+    }
+
+    /// Sets `Config::timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets `Config::retry`.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Required for an `https` address, even if only to trust the system's
+    /// root certificates with `TlsConfig::new()`.
+    pub fn tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Sets `Config::gzip`.
+    pub fn gzip(mut self, enable: bool) -> Self {
+        self.gzip = enable;
+        self
+    }
+
+    /// Sets `Config::batch_concurrency`, at least 1.
+    pub fn batch_concurrency(mut self, max: usize) -> Self {
+        self.batch_concurrency = Some(max.max(1));
+        self
+    }
+
+    /// Sets `Config::max_event_payload`.
+    pub fn max_event_payload(mut self, max: usize) -> Self {
+        self.max_event_payload = Some(max);
+        self
+    }
+
+    /// Tells `observer` about each request the client sends.
+    #[cfg(feature = "metrics")]
+    pub fn observer(mut self, observer: impl RequestObserver + 'static) -> Self {
+        self.observer = Observer {
+            inner: Some(Arc::new(observer)),
+        };
+        self
+    }
+
+    /// How many idle connections to keep open to the agent for reuse, no
+    /// limit by default. Lower it for clients that only burst now and then.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.transport.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// How long an idle connection is kept for reuse, 90 seconds by default.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.transport.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Enables TCP keep-alive probes at this interval, off by default. Helps
+    /// pooled connections survive the idle timeouts of firewalls and NATs.
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.transport.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Uses `client` for the requests instead of building one, e.g. to go
+    /// through a proxy or share a connection pool. TLS and the connection
+    /// settings are then up to `client`, these methods of the builder cannot
+    /// be combined with it.
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Besides `http` and `https` addresses, takes `unix:///path/to/socket`
+    /// for an agent listening on a Unix socket.
+    pub fn build(self) -> Result<Client> {
+        let address = self
+            .address
+            .unwrap_or_else(|| String::from("http://localhost:8500"));
+        let (url, unix_socket) = match unix_socket(&address) {
+            Some(socket) if socket.as_os_str().is_empty() => {
+                return Err(Error::from(format!(
+                    "Invalid Consul address '{}', the socket path is missing",
+                    address
+                )));
+            }
+            Some(socket) => (String::from(UNIX_SOCKET_ADDRESS), Some(socket)),
+            None => {
+                let url = Url::parse(&address)
+                    .chain_err(|| format!("Invalid Consul address '{}'", address))?;
+                if url.scheme() != "http" && url.scheme() != "https" {
+                    return Err(Error::from(format!(
+                        "Invalid Consul address '{}', the scheme must be http or https",
+                        address
+                    )));
+                }
+                (address.trim_end_matches('/').to_owned(), None)
+            }
+        };
+        let https = unix_socket.is_none() && url.starts_with("https://");
+
+        let http_client = match self.http_client {
+            Some(_) if self.tls.is_some() || self.transport != Transport::default() => {
+                return Err(Error::from(
+                    "ClientBuilder::tls and the connection settings cannot be combined with \
+                     ClientBuilder::with_http_client, configure them on the given client instead",
+                ))
+            }
+            Some(client) => client,
+            None => {
+                let builder = self.transport.apply(HttpClientBuilder::new());
+                match &self.tls {
+                    Some(tls) => tls.apply(builder)?,
+                    None if https => {
+                        return Err(Error::from(format!(
+                            "Consul address '{}' uses https but no TLS configuration was given, \
+                             see ClientBuilder::tls",
+                            address
+                        )))
+                    }
+                    None => builder,
+                }
+                .build()
+                .chain_err(|| "Failed to build reqwest client")?
+            }
+        };
+
+        Ok(Client::new(Config {
+            datacenter: self.datacenter,
+            token: self.token,
+            timeout: self.timeout,
+            retry: self.retry,
+            namespace: self.namespace,
+            partition: self.partition,
+            gzip: self.gzip,
+            batch_concurrency: self.batch_concurrency.unwrap_or(DEFAULT_BATCH_CONCURRENCY),
+            max_event_payload: self.max_event_payload.unwrap_or(event::MAX_PAYLOAD_SIZE),
+            unix_socket,
+            observer: self.observer,
+            ..Config::with_client(url, http_client)
+        }))
+    }
+}
+
+/// The default of `Config::batch_concurrency`.
+const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// What `Config::address` holds for a Unix socket, request URLs need a host.
+const UNIX_SOCKET_ADDRESS: &str = "http://localhost";
+
+fn unix_socket(address: &str) -> Option<PathBuf> {
+    address.strip_prefix("unix://").map(PathBuf::from)
+}
+
+fn tls_from_env() -> TlsConfig {
+    let mut tls = TlsConfig::new();
+    if let Ok(ca_cert) = env::var("CONSUL_CACERT") {
+        tls = tls.ca_cert_path(ca_cert);
+    }
+    if let (Ok(cert), Ok(key)) = (
+        env::var("CONSUL_CLIENT_CERT"),
+        env::var("CONSUL_CLIENT_KEY"),
+    ) {
+        tls = tls.client_cert_path(cert, key);
+    }
+    tls
+}
+
+fn address_from_env() -> String {
+    let addr = env::var("CONSUL_HTTP_ADDR").unwrap_or_else(|_| String::from("127.0.0.1:8500"));
+    if addr.starts_with("http://") || addr.starts_with("https://") || addr.starts_with("unix://") {
+        return addr;
+    }
+    // Same spellings as Go's strconv.ParseBool, which the consul CLI uses
+    let ssl = matches!(
+        env::var("CONSUL_HTTP_SSL").as_deref(),
+        Ok("1") | Ok("t") | Ok("T") | Ok("true") | Ok("TRUE") | Ok("True")
+    );
+    if ssl {
+        format!("https://{}", addr)
+    } else {
+        format!("http://{}", addr)
+    }
+}
+
+#[derive(Clone)]
 pub struct Config {
     pub address: String,
     pub datacenter: Option<String>,
     pub http_client: HttpClient,
     pub token: Option<String>,
+    /// Bounds each request, from connecting until the body is read; an
+    /// expired request fails with `ErrorKind::Timeout`. Keep it above the
+    /// `wait_time` of any blocking query.
+    pub timeout: Option<Duration>,
+    /// Retries reads, and the writes opted in through `WriteOptions::retry`,
+    /// that failed to connect or got a 5xx answer. No retries when `None`.
+    pub retry: Option<RetryPolicy>,
+    /// Consul Enterprise namespace of every request, sent as `?ns=`. Consul
+    /// OSS answers requests for a namespace other than `default` with an
+    /// error, returned as `ErrorKind::Consul`.
+    pub namespace: Option<String>,
+    /// Consul Enterprise admin partition of every request, sent as
+    /// `?partition=`. A `partition` set in `QueryOptions`/`WriteOptions`
+    /// takes precedence for that request; the namespace, if any, is then
+    /// looked up within the partition.
+    pub partition: Option<String>,
+    /// Asks Consul to gzip the responses, which pays off for the large
+    /// catalog and health listings of big clusters. Streamed responses, such
+    /// as `Agent::monitor`'s, are never compressed.
+    pub gzip: bool,
+    /// How many requests batch calls such as `KV::get_many` keep in flight,
+    /// at least 1.
+    pub batch_concurrency: usize,
+    /// The largest payload `Event::fire` sends, `event::MAX_PAYLOAD_SIZE`
+    /// unless the agents were built with another limit.
+    pub max_event_payload: usize,
+    /// Sends the requests over this Unix socket instead of TCP, `address`
+    /// then only serving to form the request URLs.
+    pub unix_socket: Option<PathBuf>,
     pub wait_time: Option<Duration>,
+    /// Told about each request, see `ClientBuilder::observer`.
+    pub observer: Observer,
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("address", &self.address)
+            .field("datacenter", &self.datacenter)
+            .field("http_client", &self.http_client)
+            .field("token", &redact(&self.token))
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .field("namespace", &self.namespace)
+            .field("partition", &self.partition)
+            .field("gzip", &self.gzip)
+            .field("batch_concurrency", &self.batch_concurrency)
+            .field("max_event_payload", &self.max_event_payload)
+            .field("unix_socket", &self.unix_socket)
+            .field("wait_time", &self.wait_time)
+            .field("observer", &self.observer)
+            .finish()
+    }
 }
 
 impl Config {
     pub fn new() -> Result<Config> {
-        ClientBuilder::new()
-            .build()
-            .chain_err(|| "Failed to build reqwest client")
-            .map(|client| Config {
-                address: String::from("http://localhost:8500"),
-                datacenter: None,
-                http_client: client,
-                token: None,
-                wait_time: None,
-            })
+        default_http_client()
+            .map(|client| Config::with_client(String::from("http://localhost:8500"), client))
     }
 
+    /// Reads the environment variables of the `consul` CLI, see
+    /// `ClientBuilder::from_env`.
     pub fn new_from_env() -> Result<Config> {
-        let consul_addr = match env::var("CONSUL_HTTP_ADDR") {
-            Ok(val) => {
-                if val.starts_with("http") {
-                    val
-                } else {
-                    format!("http://{}", val)
-                }
-            }
-            Err(_e) => String::from("http://127.0.0.1:8500"),
-        };
-        let consul_token = env::var("CONSUL_HTTP_TOKEN").ok();
-        ClientBuilder::new()
+        ClientBuilder::from_env()
             .build()
-            .chain_err(|| "Failed to build reqwest client")
-            .map(|client| Config {
-                address: consul_addr,
-                datacenter: None,
-                http_client: client,
-                token: consul_token,
-                wait_time: None,
-            })
+            .map(|client| client.config)
     }
 
     pub fn new_from_consul_host(
@@ -88,38 +480,350 @@ impl Config {
         port: Option<u16>,
         token: Option<String>,
     ) -> Result<Config> {
-        ClientBuilder::new()
-            .build()
-            .chain_err(|| "Failed to build reqwest client")
-            .map(|client| Config {
-                address: format!("{}:{}", host, port.unwrap_or(8500)),
-                datacenter: None,
-                http_client: client,
-                token,
-                wait_time: None,
-            })
+        default_http_client().map(|client| Config {
+            token,
+            ..Config::with_client(format!("{}:{}", host, port.unwrap_or(8500)), client)
+        })
+    }
+
+    /// The defaults every constructor starts from.
+    fn with_client(address: String, http_client: HttpClient) -> Config {
+        Config {
+            address,
+            datacenter: None,
+            http_client,
+            token: None,
+            timeout: None,
+            retry: None,
+            namespace: None,
+            partition: None,
+            gzip: false,
+            batch_concurrency: DEFAULT_BATCH_CONCURRENCY,
+            max_event_payload: event::MAX_PAYLOAD_SIZE,
+            unix_socket: None,
+            wait_time: None,
+            observer: Observer::default(),
+        }
+    }
+}
+
+fn default_http_client() -> Result<HttpClient> {
+    HttpClientBuilder::new()
+        .build()
+        .chain_err(|| "Failed to build reqwest client")
+}
+
+/// How failed requests are retried, see `Config::retry`.
+///
+/// The delay before the n-th retry is `base_delay * 2^(n-1)`, bounded by
+/// `max_delay`. A `Retry-After` header sent by Consul takes precedence, but
+/// is still bounded by `max_delay`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        1u32.checked_shl(attempt)
+            .and_then(|factor| self.base_delay.checked_mul(factor))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+macro_rules! name_type {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(pub String);
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(name: &str) -> Self {
+                $name(name.to_owned())
+            }
+        }
+
+        impl From<&String> for $name {
+            fn from(name: &String) -> Self {
+                $name(name.clone())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(name: String) -> Self {
+                $name(name)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+name_type!(
+    /// The name of a datacenter, e.g. `dc1`.
+    Datacenter
+);
+name_type!(
+    /// The name of a node, as `Catalog::nodes` lists them.
+    NodeName
+);
+name_type!(
+    /// The name of a service, shared by all of its instances.
+    ServiceName
+);
+
+/// Stands in for a secret in `Debug` output, so that tokens do not end up in
+/// logs.
+pub(crate) struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+pub(crate) fn redact(secret: &Option<String>) -> Option<Redacted> {
+    secret.as_ref().map(|_| Redacted)
+}
+
+/// The `metrics::RequestObserver` of a client, set with
+/// `ClientBuilder::observer`. Opaque, so that `Config` has the same fields
+/// with or without the `metrics` feature.
+#[derive(Clone, Default)]
+pub struct Observer {
+    #[cfg(feature = "metrics")]
+    inner: Option<Arc<dyn RequestObserver>>,
+}
+
+impl fmt::Debug for Observer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observer").finish_non_exhaustive()
+    }
+}
+
+/// A `Duration` (de)serialized the way Consul writes them: read from either
+/// a Go duration string such as `"1h30m"` or an integer number of
+/// nanoseconds, and always written back as a string.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct GoDuration(pub Duration);
+
+impl From<Duration> for GoDuration {
+    fn from(duration: Duration) -> Self {
+        GoDuration(duration)
+    }
+}
+
+impl From<GoDuration> for Duration {
+    fn from(duration: GoDuration) -> Self {
+        duration.0
+    }
+}
+
+impl fmt::Display for GoDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&encoding::go_duration::format(self.0))
+    }
+}
+
+impl std::str::FromStr for GoDuration {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        encoding::go_duration::parse(s)
+            .map(GoDuration)
+            .ok_or_else(|| Error::from(format!("Invalid duration: {}", s)))
     }
 }
 
-#[derive(Clone, Debug, Default)]
+impl serde::Serialize for GoDuration {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encoding::go_duration::format(self.0))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for GoDuration {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        encoding::go_duration::deserialize(deserializer).map(GoDuration)
+    }
+}
+
+/// https://www.consul.io/api/features/consistency
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ConsistencyMode {
+    /// Reads are served by the leader, which may briefly be stale after a
+    /// leadership change.
+    #[default]
+    Default,
+    /// Forces the leader to confirm it still holds leadership before
+    /// answering, at the cost of an extra round trip.
+    Consistent,
+    /// Lets any server answer, spreading reads across followers at the
+    /// price of possibly outdated results. Check `QueryMeta::last_contact`.
+    Stale,
+}
+
+/// Options of a read.
+///
+/// Consul's list endpoints are not paginated: the catalog and health APIs
+/// always answer with the full result set, and there is no page size or
+/// cursor to pass. To keep large catalogs manageable, narrow the results
+/// server-side with `filter`, and follow changes with blocking queries
+/// (`wait_index`) rather than re-listing everything.
+#[derive(Clone, Default)]
 pub struct QueryOptions {
+    /// Datacenter to query, overriding `Config::datacenter` for this read.
     pub datacenter: Option<String>,
+    /// Namespace to query, overriding `Config::namespace` for this read.
+    pub namespace: Option<String>,
+    /// Admin partition to query, overriding `Config::partition` for this read.
+    pub partition: Option<String>,
+    /// ACL token of this read, sent instead of `Config::token`.
+    pub token: Option<String>,
+    /// A filter expression applied by Consul to the results, e.g.
+    /// `Checks.Status == "passing"`; honored by the list endpoints of the
+    /// catalog, health, agent and ACL APIs among others.
+    /// https://www.consul.io/api-docs/features/filtering
+    pub filter: Option<String>,
+    /// Sorts the results by estimated round trip time from a node: a node
+    /// name, `_agent` for the agent answering the query, or `_ip` for the
+    /// node at the client's address, which behind a proxy Consul takes from
+    /// `X-Forwarded-For`. Honored by the catalog and health service and
+    /// node lists and by prepared queries.
+    /// https://www.consul.io/api-docs/health#near-1
+    pub near: Option<String>,
+    /// Only returns nodes carrying all of these metadata pairs, e.g.
+    /// `zone` => `us-east-1a`. Honored by the catalog node and service lists
+    /// and the health service list.
+    pub node_meta: HashMap<String, String>,
+    pub consistency: ConsistencyMode,
+    /// Turns the read into a blocking query: Consul holds the request until
+    /// the result's index moves past this value or `wait_time` elapses.
+    /// Feed it the `last_index` of the previous response.
     pub wait_index: Option<u64>,
+    /// Upper bound for a blocking query, Consul defaults to 5 minutes.
     pub wait_time: Option<Duration>,
 }
 
+impl fmt::Debug for QueryOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryOptions")
+            .field("datacenter", &self.datacenter)
+            .field("namespace", &self.namespace)
+            .field("partition", &self.partition)
+            .field("token", &redact(&self.token))
+            .field("filter", &self.filter)
+            .field("near", &self.near)
+            .field("node_meta", &self.node_meta)
+            .field("consistency", &self.consistency)
+            .field("wait_index", &self.wait_index)
+            .field("wait_time", &self.wait_time)
+            .finish()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct QueryMeta {
+    /// The `X-Consul-Index` of the response, to be passed back as
+    /// `QueryOptions::wait_index`.
     pub last_index: Option<u64>,
+    /// Whether the server answering the read knew of a cluster leader.
+    pub known_leader: bool,
+    /// How long ago the answering server last heard from the leader; always
+    /// zero for reads served by the leader itself.
+    pub last_contact: Duration,
     pub request_time: Duration,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Default)]
 pub struct WriteOptions {
+    /// Datacenter to write to, overriding `Config::datacenter` for this write.
     pub datacenter: Option<String>,
+    /// Namespace to write to, overriding `Config::namespace` for this write.
+    pub namespace: Option<String>,
+    /// Admin partition to write to, overriding `Config::partition` for this
+    /// write.
+    pub partition: Option<String>,
+    /// ACL token of this write, sent instead of `Config::token`.
+    pub token: Option<String>,
+    /// Retries this write under `Config::retry`. Only set it for writes that
+    /// are safe to repeat, a retried request may have been applied already.
+    pub retry: bool,
+}
+
+impl fmt::Debug for WriteOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriteOptions")
+            .field("datacenter", &self.datacenter)
+            .field("namespace", &self.namespace)
+            .field("partition", &self.partition)
+            .field("token", &redact(&self.token))
+            .field("retry", &self.retry)
+            .finish()
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct WriteMeta {
+    /// The `X-Consul-Index` of the response, when Consul sends one. Most
+    /// writes, the catalog's included, come without: read the object back,
+    /// e.g. with `Catalog::node`, to learn the index it was written at.
+    pub last_index: Option<u64>,
     pub request_time: Duration,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::GoDuration;
+    use std::time::Duration;
+
+    #[test]
+    fn go_duration_serde() {
+        let parsed: GoDuration = serde_json::from_str("\"1h30m\"").unwrap();
+        assert_eq!(parsed, GoDuration(Duration::from_secs(5400)));
+        // Consul reads back `LockDelay` and the like as nanoseconds
+        let parsed: GoDuration = serde_json::from_str("15000000000").unwrap();
+        assert_eq!(parsed, GoDuration(Duration::from_secs(15)));
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), "\"15s\"");
+        assert!(serde_json::from_str::<GoDuration>("\"15 parsecs\"").is_err());
+        assert!(serde_json::from_str::<GoDuration>("null").is_err());
+        assert_eq!(
+            serde_json::from_str::<Option<GoDuration>>("null").unwrap(),
+            None
+        );
+        assert_eq!("250ms".parse::<GoDuration>().unwrap().to_string(), "250ms");
+    }
+}
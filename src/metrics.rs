@@ -0,0 +1,83 @@
+//! Hooks for request metrics, enabled by the `metrics` feature.
+//!
+//! The crate does not depend on a metrics backend: implement
+//! `RequestObserver` to feed the counts and latencies of the requests to
+//! Prometheus, OpenTelemetry or anything else, and set it with
+//! `ClientBuilder::observer`.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+/// Told about every request the client sends, except for the streaming
+/// ones such as `Agent::monitor`'s. Retries of a request count as one
+/// request, whose latency covers them all.
+///
+/// Called inline with the requests, so implementations should only record
+/// and leave the exporting to another task.
+pub trait RequestObserver: Send + Sync {
+    /// Called as the request is about to be sent, e.g. to count requests in
+    /// flight.
+    fn before(&self, _request: &RequestInfo<'_>) {}
+
+    /// Called once the response headers are in, or the request failed.
+    fn after(&self, request: &RequestInfo<'_>, status: StatusClass, elapsed: Duration);
+}
+
+/// What a request is about, with few enough values to serve as labels.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RequestInfo<'a> {
+    /// The HTTP method, e.g. `GET`.
+    pub method: &'a str,
+    /// The family of endpoints, the path segment after `/v1/`: `kv`,
+    /// `catalog`, `health`, `agent`...
+    pub endpoint: &'a str,
+}
+
+impl<'a> RequestInfo<'a> {
+    pub(crate) fn new(method: &'a str, path: &'a str) -> Self {
+        let endpoint = path
+            .strip_prefix("/v1/")
+            .and_then(|p| p.split('/').next())
+            .unwrap_or_default();
+        RequestInfo { method, endpoint }
+    }
+}
+
+/// The outcome of a request, by class of HTTP status.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StatusClass {
+    Success,
+    Redirection,
+    ClientError,
+    ServerError,
+    /// No response, e.g. the connection or the request timed out.
+    Failed,
+}
+
+impl StatusClass {
+    /// `2xx`, `3xx`, `4xx`, `5xx`, or `failed`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatusClass::Success => "2xx",
+            StatusClass::Redirection => "3xx",
+            StatusClass::ClientError => "4xx",
+            StatusClass::ServerError => "5xx",
+            StatusClass::Failed => "failed",
+        }
+    }
+}
+
+impl From<StatusCode> for StatusClass {
+    fn from(status: StatusCode) -> Self {
+        if status.is_server_error() {
+            StatusClass::ServerError
+        } else if status.is_client_error() {
+            StatusClass::ClientError
+        } else if status.is_redirection() {
+            StatusClass::Redirection
+        } else {
+            StatusClass::Success
+        }
+    }
+}
@@ -0,0 +1,459 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use serde::de::IgnoredAny;
+
+use crate::encoding::rfc3339;
+use crate::errors::{ErrorKind, Result, ResultExt};
+use crate::request::{delete, get, post, put, put_bytes};
+use crate::{Client, GoDuration, QueryOptions, WriteMeta, WriteOptions};
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct RaftServer {
+    /// The server's Raft ID, its node ID on recent Consul versions.
+    pub ID: String,
+    pub Node: String,
+    /// The server's Raft `host:port`.
+    pub Address: String,
+    pub Leader: bool,
+    pub ProtocolVersion: String,
+    /// Whether the server takes part in quorum, non-voters only replicate.
+    pub Voter: bool,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct RaftConfiguration {
+    pub Servers: Vec<RaftServer>,
+    /// The Raft index the configuration was read at.
+    pub Index: u64,
+}
+
+/// The gossip keys of one pool, as seen by one datacenter's members.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct KeyringResponse {
+    /// Whether this is the WAN pool of the servers rather than a LAN pool.
+    pub WAN: bool,
+    pub Datacenter: String,
+    /// Number of nodes holding each installed key. A key held by fewer than
+    /// `NumNodes` nodes is not safe to `keyring_use` yet.
+    pub Keys: HashMap<String, usize>,
+    pub NumNodes: usize,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AutopilotConfiguration {
+    /// Removes dead servers from the Raft peers when a new server joins.
+    pub CleanupDeadServers: bool,
+    /// A server not heard from by the leader for longer is unhealthy.
+    pub LastContactThreshold: Option<GoDuration>,
+    /// A server this many log entries behind the leader is unhealthy.
+    pub MaxTrailingLogs: u64,
+    /// How long a new server must stay healthy before it becomes a voter.
+    pub ServerStabilizationTime: Option<GoDuration>,
+    pub RedundancyZoneTag: String,
+    pub DisableUpgradeMigration: bool,
+    pub UpgradeVersionTag: String,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    /// The index to pass to `autopilot_cas_configuration`.
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServerHealth {
+    pub ID: String,
+    pub Name: String,
+    pub Address: String,
+    pub SerfStatus: String,
+    pub Version: String,
+    pub Leader: bool,
+    pub LastContact: Option<GoDuration>,
+    pub LastTerm: u64,
+    pub LastIndex: u64,
+    pub Healthy: bool,
+    pub Voter: bool,
+    /// When the server last became healthy, an RFC 3339 timestamp.
+    pub StableSince: String,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct OperatorHealthReply {
+    pub Healthy: bool,
+    /// Number of servers that could fail without losing quorum.
+    pub FailureTolerance: usize,
+    pub Servers: Vec<ServerHealth>,
+}
+
+/// A network area, federating with another datacenter's servers over TCP
+/// rather than WAN gossip. Consul Enterprise only.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Area {
+    /// Generated by Consul on creation.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ID: String,
+    /// The datacenter the area connects to, only one area per peer.
+    pub PeerDatacenter: String,
+    /// Addresses of the peer's servers, joined on start and retried until
+    /// one succeeds.
+    pub RetryJoin: Vec<String>,
+    /// Whether the area's traffic requires TLS.
+    pub UseTLS: bool,
+}
+
+/// The outcome of joining one address in `Operator::area_join`.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AreaJoinResponse {
+    pub Address: String,
+    pub Joined: bool,
+    /// Why the join failed, empty when `Joined`.
+    pub Error: String,
+}
+
+/// An Enterprise license, as `Operator::license_get` reads it.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct License {
+    pub LicenseID: String,
+    pub CustomerID: String,
+    pub InstallationID: String,
+    pub IssueTime: String,
+    pub StartTime: String,
+    /// When the license expires, an RFC 3339 timestamp; see `expiration`.
+    pub ExpirationTime: String,
+    /// When Consul stops working after the expiration.
+    pub TerminationTime: String,
+    pub Product: String,
+    /// The Enterprise features the license grants.
+    pub Features: Vec<String>,
+}
+
+impl License {
+    /// `ExpirationTime` parsed, `None` if missing or malformed.
+    pub fn expiration(&self) -> Option<SystemTime> {
+        rfc3339::parse(&self.ExpirationTime)
+    }
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct LicenseReply {
+    pub Valid: bool,
+    pub License: License,
+    pub Warnings: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct AreaID {
+    ID: String,
+}
+
+#[derive(Serialize)]
+struct KeyringRequest<'a> {
+    Key: &'a str,
+}
+
+#[async_trait]
+pub trait Operator {
+    async fn raft_configuration(&self, q: Option<&QueryOptions>) -> Result<RaftConfiguration>;
+    async fn raft_remove_peer_by_id(&self, id: &str, q: Option<&WriteOptions>)
+        -> Result<WriteMeta>;
+    async fn keyring_list(&self) -> Result<Vec<KeyringResponse>>;
+    async fn keyring_install(&self, key: &str) -> Result<()>;
+    async fn keyring_use(&self, key: &str) -> Result<()>;
+    async fn keyring_remove(&self, key: &str) -> Result<()>;
+    async fn autopilot_configuration(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<AutopilotConfiguration>;
+    async fn autopilot_set_configuration(
+        &self,
+        cfg: &AutopilotConfiguration,
+        q: Option<&WriteOptions>,
+    ) -> Result<()>;
+    async fn autopilot_cas_configuration(
+        &self,
+        cfg: &AutopilotConfiguration,
+        index: u64,
+        q: Option<&WriteOptions>,
+    ) -> Result<bool>;
+    async fn autopilot_health(&self, q: Option<&QueryOptions>) -> Result<OperatorHealthReply>;
+    async fn segment_list(&self, q: Option<&QueryOptions>) -> Result<Vec<String>>;
+    async fn area_create(&self, area: &Area, q: Option<&WriteOptions>) -> Result<String>;
+    async fn area_list(&self, q: Option<&QueryOptions>) -> Result<Vec<Area>>;
+    async fn area_update(&self, area: &Area, q: Option<&WriteOptions>) -> Result<()>;
+    async fn area_delete(&self, id: &str, q: Option<&WriteOptions>) -> Result<()>;
+    async fn area_join(
+        &self,
+        id: &str,
+        addresses: &[&str],
+        q: Option<&WriteOptions>,
+    ) -> Result<Vec<AreaJoinResponse>>;
+    async fn license_get(&self, q: Option<&QueryOptions>) -> Result<LicenseReply>;
+    async fn license_put(&self, license: &str, q: Option<&WriteOptions>) -> Result<LicenseReply>;
+}
+
+#[async_trait]
+impl Operator for Client {
+    /// https://www.consul.io/api/operator/raft.html#read-configuration
+    async fn raft_configuration(&self, q: Option<&QueryOptions>) -> Result<RaftConfiguration> {
+        get(
+            "/v1/operator/raft/configuration",
+            &self.config,
+            Vec::new(),
+            q,
+        )
+        .await
+        .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/operator/raft.html#delete-raft-peer
+    ///
+    /// Evicts a server from the Raft configuration by its `RaftServer::ID`,
+    /// typically one that failed and will not come back.
+    async fn raft_remove_peer_by_id(
+        &self,
+        id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<WriteMeta> {
+        let params = vec![(String::from("id"), id.to_owned())];
+        delete(
+            "/v1/operator/raft/peer",
+            None as Option<&()>,
+            &self.config,
+            params,
+            q,
+        )
+        .await
+        .map(|x: (Option<()>, WriteMeta)| x.1)
+    }
+
+    /// https://www.consul.io/api/operator/keyring.html#list-gossip-encryption-keys
+    async fn keyring_list(&self) -> Result<Vec<KeyringResponse>> {
+        get("/v1/operator/keyring", &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/operator/keyring.html#add-new-gossip-encryption-key
+    ///
+    /// Distributes `key`, a base64-encoded 32 byte key, to every member of
+    /// the cluster without using it yet.
+    async fn keyring_install(&self, key: &str) -> Result<()> {
+        post(
+            "/v1/operator/keyring",
+            Some(&KeyringRequest { Key: key }),
+            &self.config,
+            Vec::new(),
+            None,
+        )
+        .await
+        .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/operator/keyring.html#change-primary-gossip-encryption-key
+    ///
+    /// Makes an installed `key` the one used to encrypt gossip.
+    async fn keyring_use(&self, key: &str) -> Result<()> {
+        put(
+            "/v1/operator/keyring",
+            Some(&KeyringRequest { Key: key }),
+            &self.config,
+            Vec::new(),
+            None,
+        )
+        .await
+        .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/operator/keyring.html#delete-gossip-encryption-key
+    ///
+    /// The primary key cannot be removed, switch to another one first.
+    async fn keyring_remove(&self, key: &str) -> Result<()> {
+        delete(
+            "/v1/operator/keyring",
+            Some(&KeyringRequest { Key: key }),
+            &self.config,
+            Vec::new(),
+            None,
+        )
+        .await
+        .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/operator/autopilot.html#read-configuration
+    async fn autopilot_configuration(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<AutopilotConfiguration> {
+        get(
+            "/v1/operator/autopilot/configuration",
+            &self.config,
+            Vec::new(),
+            q,
+        )
+        .await
+        .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/operator/autopilot.html#update-configuration
+    async fn autopilot_set_configuration(
+        &self,
+        cfg: &AutopilotConfiguration,
+        q: Option<&WriteOptions>,
+    ) -> Result<()> {
+        put(
+            "/v1/operator/autopilot/configuration",
+            Some(cfg),
+            &self.config,
+            Vec::new(),
+            q,
+        )
+        .await
+        .map(|_: (bool, WriteMeta)| ())
+    }
+
+    /// https://www.consul.io/api/operator/autopilot.html#update-configuration
+    ///
+    /// Writes `cfg` only if the configuration's `ModifyIndex` still equals
+    /// `index`, returning whether the write happened.
+    async fn autopilot_cas_configuration(
+        &self,
+        cfg: &AutopilotConfiguration,
+        index: u64,
+        q: Option<&WriteOptions>,
+    ) -> Result<bool> {
+        let params = vec![(String::from("cas"), index.to_string())];
+        put(
+            "/v1/operator/autopilot/configuration",
+            Some(cfg),
+            &self.config,
+            params,
+            q,
+        )
+        .await
+        .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/operator/autopilot.html#read-health
+    ///
+    /// An unhealthy cluster is reported through `Healthy`, not as an error.
+    async fn autopilot_health(&self, q: Option<&QueryOptions>) -> Result<OperatorHealthReply> {
+        match get("/v1/operator/autopilot/health", &self.config, Vec::new(), q).await {
+            Ok(x) => Ok(x.0),
+            // Consul answers 429 along with the report when unhealthy
+            Err(e) => match e.kind() {
+                ErrorKind::Consul(429, body) => {
+                    serde_json::from_str(body).chain_err(|| "Failed to parse JSON response")
+                }
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// https://www.consul.io/api/operator/segment.html#list-network-segments
+    ///
+    /// The names of the network segments, the default segment's being the
+    /// empty string. Consul OSS has no segments and its answer maps to
+    /// `ErrorKind::EnterpriseOnly`.
+    async fn segment_list(&self, q: Option<&QueryOptions>) -> Result<Vec<String>> {
+        let result = get("/v1/operator/segment", &self.config, Vec::new(), q).await;
+        enterprise_only(result, "Network segments").map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/operator/area.html#create-network-area
+    ///
+    /// Returns the ID of the new area. Like the other area calls, this fails
+    /// with `ErrorKind::EnterpriseOnly` against Consul OSS.
+    async fn area_create(&self, area: &Area, q: Option<&WriteOptions>) -> Result<String> {
+        let result = post("/v1/operator/area", Some(area), &self.config, Vec::new(), q).await;
+        enterprise_only(result, "Network areas").map(|x: (AreaID, _)| x.0.ID)
+    }
+
+    /// https://www.consul.io/api/operator/area.html#list-network-areas
+    async fn area_list(&self, q: Option<&QueryOptions>) -> Result<Vec<Area>> {
+        let result = get("/v1/operator/area", &self.config, Vec::new(), q).await;
+        enterprise_only(result, "Network areas").map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/operator/area.html#update-network-area
+    ///
+    /// The area to update is identified by its `ID`, its `PeerDatacenter`
+    /// cannot change.
+    async fn area_update(&self, area: &Area, q: Option<&WriteOptions>) -> Result<()> {
+        let path = format!("/v1/operator/area/{}", area.ID);
+        let result = put(&path, Some(area), &self.config, Vec::new(), q).await;
+        enterprise_only(result, "Network areas").map(|_: (IgnoredAny, _)| ())
+    }
+
+    /// https://www.consul.io/api/operator/area.html#delete-network-area
+    async fn area_delete(&self, id: &str, q: Option<&WriteOptions>) -> Result<()> {
+        let path = format!("/v1/operator/area/{}", id);
+        let result = delete(&path, None as Option<&()>, &self.config, Vec::new(), q).await;
+        enterprise_only(result, "Network areas").map(|_: (IgnoredAny, _)| ())
+    }
+
+    /// https://www.consul.io/api/operator/area.html#join-network-area
+    ///
+    /// Joins the peer's servers at `addresses`, reporting how each join
+    /// went; a failed join is not an error.
+    async fn area_join(
+        &self,
+        id: &str,
+        addresses: &[&str],
+        q: Option<&WriteOptions>,
+    ) -> Result<Vec<AreaJoinResponse>> {
+        let path = format!("/v1/operator/area/{}/join", id);
+        let result = put(&path, Some(&addresses), &self.config, Vec::new(), q).await;
+        enterprise_only(result, "Network areas").map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/operator/license.html#getting-the-consul-license
+    ///
+    /// Fails with `ErrorKind::EnterpriseOnly` against Consul OSS.
+    async fn license_get(&self, q: Option<&QueryOptions>) -> Result<LicenseReply> {
+        let result = get("/v1/operator/license", &self.config, Vec::new(), q).await;
+        enterprise_only(result, "Licenses").map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/operator/license.html#updating-the-consul-license
+    ///
+    /// Installs the signed `license` and returns it as `license_get` would.
+    /// Consul 1.10 and later only load licenses from their configuration and
+    /// reject this call.
+    async fn license_put(&self, license: &str, q: Option<&WriteOptions>) -> Result<LicenseReply> {
+        let result = put_bytes(
+            "/v1/operator/license",
+            license.as_bytes(),
+            &self.config,
+            Vec::new(),
+            q,
+        )
+        .await;
+        enterprise_only(result, "Licenses").map(|x| x.0)
+    }
+}
+
+/// Maps Consul OSS's answer to an Enterprise endpoint, either the empty 404
+/// of an unknown path or an error naming Consul Enterprise, to
+/// `ErrorKind::EnterpriseOnly`. Other 404s, such as Consul Enterprise's for
+/// an unknown area, are left as they are.
+fn enterprise_only<T>(result: Result<T>, feature: &str) -> Result<T> {
+    result.map_err(|e| match e.kind() {
+        ErrorKind::Consul(status, body)
+            if (*status == 404 && body.trim().is_empty()) || body.contains("Enterprise") =>
+        {
+            e.chain_err(|| ErrorKind::EnterpriseOnly(feature.to_owned()))
+        }
+        _ => e,
+    })
+}
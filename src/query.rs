@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use async_trait::async_trait;
+
+use crate::errors::Result;
+use crate::health::ServiceEntry;
+use crate::request::{delete, get, get_vec, post, put};
+use crate::{Client, QueryMeta, QueryOptions, Redacted, WriteMeta, WriteOptions};
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct QueryFailoverOptions {
+    /// Number of other datacenters to try, ordered by network distance.
+    pub NearestN: usize,
+    /// Datacenters to try, in this order, after the `NearestN` ones.
+    pub Datacenters: Option<Vec<String>>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceQuery {
+    pub Service: String,
+    pub Failover: QueryFailoverOptions,
+    pub OnlyPassing: bool,
+    /// Sorts the nodes by distance from this node, `_agent` meaning the agent
+    /// serving the request.
+    pub Near: String,
+    /// Tags to filter on, a `!` prefix excluding instances with the tag.
+    pub Tags: Option<Vec<String>>,
+    pub NodeMeta: Option<HashMap<String, String>>,
+    pub ServiceMeta: Option<HashMap<String, String>>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct QueryDNSOptions {
+    pub TTL: String,
+}
+
+/// Turns a query into a template, matching every name starting with the
+/// query's `Name` and interpolating the rest, e.g. `${name.suffix}`, into
+/// the query.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct QueryTemplateOptions {
+    /// `name_prefix_match`, the only type Consul knows.
+    pub Type: String,
+    /// Matched against the name the query is executed with, its capture
+    /// groups interpolated as `${match(N)}`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Regexp: String,
+    /// Drops the tags that interpolate to an empty string.
+    pub RemoveEmptyTags: bool,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreparedQueryDefinition {
+    /// Generated by Consul on creation.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ID: String,
+    /// Lets the query be executed by name and resolved as
+    /// `<name>.query.consul` in DNS.
+    pub Name: String,
+    /// Ties the query's lifetime to a session, deleting it with the session.
+    pub Session: String,
+    /// Token used when executing the query, the caller's own when empty.
+    pub Token: String,
+    pub Service: ServiceQuery,
+    pub DNS: QueryDNSOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Template: Option<QueryTemplateOptions>,
+    #[serde(skip_serializing)]
+    pub CreateIndex: u64,
+    #[serde(skip_serializing)]
+    pub ModifyIndex: u64,
+}
+
+impl fmt::Debug for PreparedQueryDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PreparedQueryDefinition")
+            .field("ID", &self.ID)
+            .field("Name", &self.Name)
+            .field("Session", &self.Session)
+            .field("Token", &(!self.Token.is_empty()).then_some(Redacted))
+            .field("Service", &self.Service)
+            .field("DNS", &self.DNS)
+            .field("Template", &self.Template)
+            .field("CreateIndex", &self.CreateIndex)
+            .field("ModifyIndex", &self.ModifyIndex)
+            .finish()
+    }
+}
+
+#[derive(Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PreparedQueryExecuteResponse {
+    pub Service: String,
+    pub Nodes: Vec<ServiceEntry>,
+    pub DNS: QueryDNSOptions,
+    /// The datacenter that answered, which differs from the local one after
+    /// a failover.
+    pub Datacenter: String,
+    /// Number of remote datacenters tried before getting an answer.
+    pub Failovers: usize,
+}
+
+/// What executing a query would run, its template rendered.
+#[derive(Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PreparedQueryExplain {
+    pub Query: PreparedQueryDefinition,
+}
+
+#[derive(Deserialize)]
+struct PreparedQueryID {
+    ID: String,
+}
+
+#[async_trait]
+pub trait Query {
+    async fn create(
+        &self,
+        query: &PreparedQueryDefinition,
+        q: Option<&WriteOptions>,
+    ) -> Result<(String, WriteMeta)>;
+    async fn update(
+        &self,
+        query: &PreparedQueryDefinition,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+    async fn delete(&self, query_id: &str, q: Option<&WriteOptions>) -> Result<((), WriteMeta)>;
+    async fn list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<PreparedQueryDefinition>, QueryMeta)>;
+    async fn get(
+        &self,
+        query_id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<PreparedQueryDefinition>, QueryMeta)>;
+    async fn execute(
+        &self,
+        query_or_name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(PreparedQueryExecuteResponse, QueryMeta)>;
+    async fn explain(
+        &self,
+        query_or_name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(PreparedQueryExplain, QueryMeta)>;
+}
+
+#[async_trait]
+impl Query for Client {
+    /// https://www.consul.io/api/query.html#create-prepared-query
+    ///
+    /// Returns the ID of the new query.
+    async fn create(
+        &self,
+        query: &PreparedQueryDefinition,
+        q: Option<&WriteOptions>,
+    ) -> Result<(String, WriteMeta)> {
+        post("/v1/query", Some(query), &self.config, Vec::new(), q)
+            .await
+            .map(|x: (PreparedQueryID, WriteMeta)| (x.0.ID, x.1))
+    }
+
+    /// https://www.consul.io/api/query.html#update-prepared-query
+    ///
+    /// The query to update is identified by its `ID`.
+    async fn update(
+        &self,
+        query: &PreparedQueryDefinition,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let path = format!("/v1/query/{}", query.ID);
+        put(&path, Some(query), &self.config, Vec::new(), q)
+            .await
+            .map(|x: (Option<()>, WriteMeta)| ((), x.1))
+    }
+
+    /// https://www.consul.io/api/query.html#delete-prepared-query
+    async fn delete(&self, query_id: &str, q: Option<&WriteOptions>) -> Result<((), WriteMeta)> {
+        let path = format!("/v1/query/{}", query_id);
+        delete(&path, None as Option<&()>, &self.config, Vec::new(), q)
+            .await
+            .map(|x: (Option<()>, WriteMeta)| ((), x.1))
+    }
+
+    /// https://www.consul.io/api/query.html#read-prepared-query
+    async fn list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<PreparedQueryDefinition>, QueryMeta)> {
+        get_vec("/v1/query", &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/query.html#read-prepared-query-1
+    async fn get(
+        &self,
+        query_id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<PreparedQueryDefinition>, QueryMeta)> {
+        let path = format!("/v1/query/{}", query_id);
+        get_vec(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/query.html#execute-prepared-query
+    ///
+    /// `query_or_name` is either the query's `ID` or its `Name`; Consul also
+    /// matches names against prefix-based query templates.
+    async fn execute(
+        &self,
+        query_or_name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(PreparedQueryExecuteResponse, QueryMeta)> {
+        let path = format!("/v1/query/{}/execute", query_or_name);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/query.html#explain-prepared-query
+    ///
+    /// Resolves `query_or_name` as `execute` would, including the template
+    /// it matches, but returns the query instead of running it.
+    async fn explain(
+        &self,
+        query_or_name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(PreparedQueryExplain, QueryMeta)> {
+        let path = format!("/v1/query/{}/explain", query_or_name);
+        get(&path, &self.config, Vec::new(), q).await
+    }
+}
@@ -1,186 +1,663 @@
-use std::collections::HashMap;
 use url::Url;
 
-use std::str;
+use std::path::Path;
 use std::str::FromStr;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use reqwest::header::HeaderValue;
+use bytes::Bytes;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH};
 use reqwest::Client as HttpClient;
 use reqwest::RequestBuilder;
-use reqwest::StatusCode;
+use reqwest::{Response, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::errors::{Result, ResultExt};
-use crate::{Config, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use crate::encoding::{go_duration, gzip};
+use crate::errors::{Error, ErrorKind, Result, ResultExt};
+#[cfg(feature = "metrics")]
+use crate::metrics::{RequestInfo, RequestObserver, StatusClass};
+use crate::{Config, ConsistencyMode, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
 
-fn add_config_options(builder: RequestBuilder, config: &Config) -> RequestBuilder {
-    match &config.token {
+fn add_config_options(
+    builder: RequestBuilder,
+    config: &Config,
+    token: Option<&str>,
+) -> RequestBuilder {
+    let builder = match &config.timeout {
+        Some(timeout) => builder.timeout(*timeout),
+        None => builder,
+    };
+    let builder = if config.gzip {
+        builder.header(ACCEPT_ENCODING, "gzip")
+    } else {
+        builder
+    };
+    add_token(builder, config, token)
+}
+
+/// Sends `token`, when the request has its own, or else `Config::token`.
+fn add_token(builder: RequestBuilder, config: &Config, token: Option<&str>) -> RequestBuilder {
+    match token.or(config.token.as_deref()) {
         Some(val) => builder.header("X-Consul-Token", val),
         None => builder,
     }
 }
 
-pub async fn get_vec<R: DeserializeOwned>(
-    path: &str,
+fn transport_error(e: reqwest::Error, description: &str) -> Error {
+    if e.is_timeout() {
+        Error::with_chain(e, ErrorKind::Timeout)
+    } else {
+        Error::with_chain(e, description)
+    }
+}
+
+/// A request that got no response, `connect` telling whether it is known
+/// not to have reached Consul and so is safe to retry.
+struct Failure {
+    error: Error,
+    connect: bool,
+}
+
+async fn dispatch(
+    builder: RequestBuilder,
     config: &Config,
-    mut params: HashMap<String, String>,
-    options: Option<&QueryOptions>,
-) -> Result<(Vec<R>, QueryMeta)> {
-    let datacenter: Option<&String> = options
-        .and_then(|o| o.datacenter.as_ref())
-        .or_else(|| config.datacenter.as_ref());
+) -> std::result::Result<Response, Failure> {
+    match &config.unix_socket {
+        Some(socket) => send_unix(builder, socket).await,
+        None => builder.send().await.map_err(|e| Failure {
+            connect: e.is_connect(),
+            error: transport_error(e, "HTTP request to consul failed"),
+        }),
+    }
+}
+
+/// Sends the request over the agent's Unix socket, on a connection of its
+/// own. The request URL only provides the path and query, and a timeout only
+/// bounds the wait for the response headers.
+#[cfg(unix)]
+async fn send_unix(
+    builder: RequestBuilder,
+    socket: &Path,
+) -> std::result::Result<Response, Failure> {
+    use hyper::header::{HeaderValue, HOST};
 
-    if let Some(dc) = datacenter {
-        params.insert(String::from("dc"), dc.to_owned());
+    let failure = |error: Error, connect: bool| Failure { error, connect };
+    let request = builder
+        .build()
+        .map_err(|e| failure(transport_error(e, "Failed to build request"), false))?;
+    let uri = match request.url().query() {
+        Some(query) if !query.is_empty() => format!("{}?{}", request.url().path(), query),
+        _ => request.url().path().to_owned(),
+    };
+    let body = match request.body().map(|b| b.as_bytes()) {
+        Some(Some(bytes)) => hyper::Body::from(bytes.to_vec()),
+        Some(None) => {
+            return Err(failure(
+                Error::from("Streamed request bodies cannot be sent over a Unix socket"),
+                false,
+            ))
+        }
+        None => hyper::Body::empty(),
+    };
+    let mut http_request = hyper::Request::builder()
+        .method(request.method().clone())
+        .uri(uri)
+        .body(body)
+        .map_err(|e| failure(Error::with_chain(e, "Failed to build request"), false))?;
+    *http_request.headers_mut() = request.headers().clone();
+    http_request
+        .headers_mut()
+        .insert(HOST, HeaderValue::from_static("localhost"));
+
+    let exchange = async {
+        let stream = tokio::net::UnixStream::connect(socket).await.map_err(|e| {
+            failure(
+                Error::with_chain(e, format!("Failed to connect to {:?}", socket)),
+                true,
+            )
+        })?;
+        let (mut sender, connection) = hyper::client::conn::handshake(stream)
+            .await
+            .map_err(|e| failure(Error::with_chain(e, "HTTP request to consul failed"), true))?;
+        tokio::spawn(async move {
+            // Ends once the response body is read, or dropped
+            let _ = connection.await;
+        });
+        sender
+            .send_request(http_request)
+            .await
+            .map(Response::from)
+            .map_err(|e| failure(Error::with_chain(e, "HTTP request to consul failed"), false))
+    };
+    match request.timeout() {
+        Some(timeout) => tokio::time::timeout(*timeout, exchange)
+            .await
+            .unwrap_or_else(|_| Err(failure(ErrorKind::Timeout.into(), false))),
+        None => exchange.await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn send_unix(_: RequestBuilder, _: &Path) -> std::result::Result<Response, Failure> {
+    Err(Failure {
+        error: "Unix sockets are not supported on this platform".into(),
+        connect: false,
+    })
+}
+
+/// Sends the request, retrying it under `Config::retry` when `retry` is set.
+/// Only connection errors and 5xx answers are retried, and requests whose
+/// body cannot be cloned are sent once. `token` overrides `Config::token`.
+async fn send(
+    builder: RequestBuilder,
+    config: &Config,
+    token: Option<&str>,
+    retry: bool,
+) -> Result<Response> {
+    #[cfg(feature = "metrics")]
+    if let Some(observer) = &config.observer.inner {
+        return observed(observer.as_ref(), builder, config, token, retry).await;
     }
+    traced(builder, config, token, retry).await
+}
+
+/// Like `traced`, telling `observer` about the request.
+#[cfg(feature = "metrics")]
+async fn observed(
+    observer: &dyn RequestObserver,
+    builder: RequestBuilder,
+    config: &Config,
+    token: Option<&str>,
+    retry: bool,
+) -> Result<Response> {
+    let request = builder.try_clone().and_then(|b| b.build().ok());
+    let info = match &request {
+        Some(request) => RequestInfo::new(request.method().as_str(), request.url().path()),
+        None => RequestInfo::new("", ""),
+    };
+    observer.before(&info);
+    let start = Instant::now();
+    let result = traced(builder, config, token, retry).await;
+    let status = match &result {
+        Ok(response) => StatusClass::from(response.status()),
+        Err(_) => StatusClass::Failed,
+    };
+    observer.after(&info, status, start.elapsed());
+    result
+}
+
+#[cfg(not(feature = "tracing"))]
+async fn traced(
+    builder: RequestBuilder,
+    config: &Config,
+    token: Option<&str>,
+    retry: bool,
+) -> Result<Response> {
+    send_attempts(builder, config, token, retry).await
+}
+
+/// Sends the request in a `consul.request` span holding the method, the
+/// path and the outcome. The path leaves out the query string and the
+/// address, where credentials could be; retries share the span.
+#[cfg(feature = "tracing")]
+async fn traced(
+    builder: RequestBuilder,
+    config: &Config,
+    token: Option<&str>,
+    retry: bool,
+) -> Result<Response> {
+    use tracing::field::{display, Empty};
+    use tracing::Instrument;
+
+    let span = tracing::debug_span!(
+        "consul.request",
+        method = Empty,
+        path = Empty,
+        status = Empty,
+        index = Empty,
+        error = Empty,
+    );
+    // Building a copy of the request is only worth it for a listened to span
+    let request = if span.is_disabled() {
+        None
+    } else {
+        builder.try_clone().and_then(|b| b.build().ok())
+    };
+    if let Some(request) = &request {
+        span.record("method", request.method().as_str());
+        span.record("path", request.url().path());
+    }
+    let result = send_attempts(builder, config, token, retry)
+        .instrument(span.clone())
+        .await;
+    match &result {
+        Ok(response) => {
+            span.record("status", response.status().as_u16());
+            if let Ok(Some(index)) = parse_header::<u64>(response.headers(), "X-Consul-Index") {
+                span.record("index", index);
+            }
+        }
+        Err(e) => {
+            span.record("error", display(e));
+        }
+    }
+    result
+}
+
+async fn send_attempts(
+    builder: RequestBuilder,
+    config: &Config,
+    token: Option<&str>,
+    retry: bool,
+) -> Result<Response> {
+    let builder = add_config_options(builder, config, token);
+    let policy = config.retry.as_ref().filter(|_| retry);
+    let mut attempt = 0;
+    loop {
+        let request = match policy {
+            Some(policy) if attempt < policy.max_retries => builder.try_clone(),
+            _ => None,
+        };
+        let (request, policy) = match (request, policy) {
+            (Some(request), Some(policy)) => (request, policy),
+            _ => break,
+        };
+        let retry_after = match dispatch(request, config).await {
+            Ok(r) if r.status().is_server_error() => parse_header(r.headers(), "Retry-After")
+                .unwrap_or(None)
+                .map(Duration::from_secs),
+            Ok(r) => return Ok(r),
+            Err(failure) if failure.connect => None,
+            Err(failure) => return Err(failure.error),
+        };
+        let delay =
+            retry_after.map_or_else(|| policy.backoff(attempt), |d| d.min(policy.max_delay));
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+    dispatch(builder, config).await.map_err(|f| f.error)
+}
+
+/// Turns a non-2xx response into `ErrorKind::Consul`, carrying Consul's
+/// error text, or into `ErrorKind::NoClusterLeader` over it when that is
+/// what the error is about.
+async fn check_status(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = read_body(response).await?;
+    let body = String::from_utf8_lossy(&body);
+    let error = Error::from(ErrorKind::Consul(status.as_u16(), body.trim().to_owned()));
+    if status == StatusCode::INTERNAL_SERVER_ERROR && body.contains("No cluster leader") {
+        return Err(error.chain_err(|| ErrorKind::NoClusterLeader));
+    }
+    Err(error)
+}
+
+fn is_gzip(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_ENCODING)
+        .is_some_and(|encoding| encoding.as_bytes().eq_ignore_ascii_case(b"gzip"))
+}
+
+/// Bounds what a gzipped body may decompress to, far above the largest
+/// listings of big clusters, so that a few compressed bytes cannot take all
+/// the memory.
+const MAX_DECODED_BODY: usize = 512 * 1024 * 1024;
+
+/// Reads the whole body, undoing the gzip `Content-Encoding` Consul applies
+/// when `Config::gzip` asks for it; a body that merely is gzip data, such as
+/// a snapshot, is returned as is.
+async fn read_body(response: Response) -> Result<Bytes> {
+    let gzipped = is_gzip(response.headers());
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| transport_error(e, "Failed to read response body"))?;
+    if !gzipped {
+        return Ok(body);
+    }
+    gzip::decode(&body, MAX_DECODED_BODY)
+        .map(Bytes::from)
+        .map_err(|e| Error::from(format!("Failed to decompress response body: {}", e)))
+}
+
+async fn parse_json<R: DeserializeOwned>(response: Response) -> Result<R> {
+    let body = read_body(response).await?;
+    // Several write endpoints answer with an empty body, read it as `null`
+    let body: &[u8] = if body.is_empty() { b"null" } else { &body };
+    serde_json::from_slice(body).chain_err(|| "Failed to parse JSON response")
+}
+
+fn query_token(options: Option<&QueryOptions>) -> Option<&str> {
+    options.and_then(|o| o.token.as_deref())
+}
+
+/// Adds the datacenter, namespace and partition of a request, those of its
+/// options taking precedence over `config`'s.
+fn add_scope(
+    params: &mut Vec<(String, String)>,
+    config: &Config,
+    datacenter: Option<&str>,
+    namespace: Option<&str>,
+    partition: Option<&str>,
+) {
+    let scope = [
+        ("dc", datacenter.or(config.datacenter.as_deref())),
+        ("ns", namespace.or(config.namespace.as_deref())),
+        ("partition", partition.or(config.partition.as_deref())),
+    ];
+    for (key, value) in scope {
+        if let Some(value) = value {
+            params.push((String::from(key), value.to_owned()));
+        }
+    }
+}
+
+fn add_query_options(
+    params: &mut Vec<(String, String)>,
+    config: &Config,
+    options: Option<&QueryOptions>,
+) {
+    add_scope(
+        params,
+        config,
+        options.and_then(|o| o.datacenter.as_deref()),
+        options.and_then(|o| o.namespace.as_deref()),
+        options.and_then(|o| o.partition.as_deref()),
+    );
     if let Some(options) = options {
+        match options.consistency {
+            ConsistencyMode::Default => {}
+            ConsistencyMode::Consistent => params.push((String::from("consistent"), String::new())),
+            ConsistencyMode::Stale => params.push((String::from("stale"), String::new())),
+        }
         if let Some(index) = options.wait_index {
-            params.insert(String::from("index"), index.to_string());
+            params.push((String::from("index"), index.to_string()));
         }
         if let Some(wait_time) = options.wait_time {
-            params.insert(String::from("wait"), format!("{}s", wait_time.as_secs()));
+            params.push((String::from("wait"), go_duration::format(wait_time)));
+        }
+        if let Some(filter) = &options.filter {
+            params.push((String::from("filter"), filter.to_owned()));
+        }
+        if let Some(near) = &options.near {
+            params.push((String::from("near"), near.to_owned()));
+        }
+        // Sorted, for the same request to always have the same URL
+        let mut node_meta: Vec<_> = options.node_meta.iter().collect();
+        node_meta.sort();
+        for (key, value) in node_meta {
+            params.push((String::from("node-meta"), format!("{}:{}", key, value)));
         }
     }
+}
+
+fn parse_header<T: FromStr>(headers: &HeaderMap, name: &str) -> Result<Option<T>> {
+    headers
+        .get(name)
+        .map(|value: &HeaderValue| -> Result<T> {
+            let s = value
+                .to_str()
+                .chain_err(|| format!("Failed to parse valid UTF8 for {}", name))?;
+            T::from_str(s)
+                .ok()
+                .ok_or_else(|| format!("Failed to parse valid value for {}", name).into())
+        })
+        .transpose()
+}
+
+fn query_meta(headers: &HeaderMap, start: Instant) -> Result<QueryMeta> {
+    Ok(QueryMeta {
+        last_index: parse_header(headers, "X-Consul-Index")?,
+        known_leader: parse_header(headers, "X-Consul-KnownLeader")?.unwrap_or(false),
+        last_contact: Duration::from_millis(
+            parse_header(headers, "X-Consul-LastContact")?.unwrap_or(0),
+        ),
+        request_time: Instant::now() - start,
+    })
+}
+
+pub async fn get_vec<R: DeserializeOwned>(
+    path: &str,
+    config: &Config,
+    mut params: Vec<(String, String)>,
+    options: Option<&QueryOptions>,
+) -> Result<(Vec<R>, QueryMeta)> {
+    add_query_options(&mut params, config, options);
 
     let url_str = format!("{}{}", config.address, path);
     let url =
         Url::parse_with_params(&url_str, params.iter()).chain_err(|| "Failed to parse URL")?;
     let start = Instant::now();
-    let request_builder = add_config_options(config.http_client.get(url), &config);
-    let r = request_builder
-        .send()
-        .await
-        .chain_err(|| "HTTP request to consul failed")?;
-    let x: Option<Result<u64>> = r
-        .headers()
-        .get("X-Consul-Index")
-        .map(|value: &HeaderValue| value.as_bytes())
-        .map(|bytes| {
-            str::from_utf8(bytes)
-                .chain_err(|| "Failed to parse valid UT8 for last index")
-                .and_then(|s| {
-                    u64::from_str(s).chain_err(|| "Failed to parse valid number for last index")
-                })
-        });
+    let r = send(
+        config.http_client.get(url),
+        config,
+        query_token(options),
+        true,
+    )
+    .await?;
+    let headers = r.headers().clone();
+    // A missing resource is an empty list rather than an error
     let j = if r.status() != StatusCode::NOT_FOUND {
-        r.json()
-            .await
-            .chain_err(|| "Failed to parse JSON response")?
+        parse_json(check_status(r).await?).await?
     } else {
         Vec::new()
     };
-    let x = match x {
-        Some(r) => (j, Some(r?)),
-        None => (j, None),
-    };
-    Ok((
-        x.0,
-        QueryMeta {
-            last_index: x.1,
-            request_time: Instant::now() - start,
-        },
-    ))
+    Ok((j, query_meta(&headers, start)?))
 }
 
 pub async fn get<R: DeserializeOwned>(
     path: &str,
     config: &Config,
-    mut params: HashMap<String, String>,
+    mut params: Vec<(String, String)>,
     options: Option<&QueryOptions>,
 ) -> Result<(R, QueryMeta)> {
-    let datacenter: Option<&String> = options
-        .and_then(|o| o.datacenter.as_ref())
-        .or_else(|| config.datacenter.as_ref());
+    add_query_options(&mut params, config, options);
 
-    if let Some(dc) = datacenter {
-        params.insert(String::from("dc"), dc.to_owned());
-    }
-    if let Some(options) = options {
-        if let Some(index) = options.wait_index {
-            params.insert(String::from("index"), index.to_string());
-        }
-        if let Some(wait_time) = options.wait_time {
-            params.insert(String::from("wait"), format!("{}s", wait_time.as_secs()));
-        }
-    }
+    let url_str = format!("{}{}", config.address, path);
+    let url =
+        Url::parse_with_params(&url_str, params.iter()).chain_err(|| "Failed to parse URL")?;
+    let start = Instant::now();
+    let r = send(
+        config.http_client.get(url),
+        config,
+        query_token(options),
+        true,
+    )
+    .await?;
+    let headers = r.headers().clone();
+    let j = parse_json(check_status(r).await?).await?;
+    Ok((j, query_meta(&headers, start)?))
+}
+
+/// Like `get`, for endpoints answering with a meaningful body under some
+/// non-2xx `statuses` (e.g. a health status): those are decoded rather than
+/// turned into errors. Retried under `Config::retry` like `get`, unless one
+/// of `statuses` is a 5xx: the request is then never retried, since the 5xx
+/// may well be the answer.
+pub async fn get_with_status<R: DeserializeOwned>(
+    path: &str,
+    config: &Config,
+    mut params: Vec<(String, String)>,
+    options: Option<&QueryOptions>,
+    statuses: &[StatusCode],
+) -> Result<(R, StatusCode, QueryMeta)> {
+    add_query_options(&mut params, config, options);
 
     let url_str = format!("{}{}", config.address, path);
     let url =
         Url::parse_with_params(&url_str, params.iter()).chain_err(|| "Failed to parse URL")?;
     let start = Instant::now();
-    let request_builder = add_config_options(config.http_client.get(url), &config);
-    let r = request_builder
-        .send()
-        .await
-        .chain_err(|| "HTTP request to consul failed")?;
-
-    let x: Option<Result<u64>> =
-        r.headers()
-            .get("X-Consul-Index")
-            .map(|bytes: &HeaderValue| -> Result<u64> {
-                bytes
-                    .to_str()
-                    .chain_err(|| "Failed to parse valid UT8 for last index")
-                    .and_then(|s: &str| -> Result<u64> {
-                        u64::from_str(s).chain_err(|| "Failed to parse valid number for last index")
-                    })
-            });
-    let j = r
-        .json()
-        .await
-        .chain_err(|| "Failed to parse JSON response")?;
-    let x = match x {
-        Some(r) => (j, Some(r?)),
-        None => (j, None),
+    // A 5xx the caller expects is an answer, not a failure to retry
+    let retry = !statuses.iter().any(|s| s.is_server_error());
+    let r = send(
+        config.http_client.get(url),
+        config,
+        query_token(options),
+        retry,
+    )
+    .await?;
+    let headers = r.headers().clone();
+    let status = r.status();
+    let r = if statuses.contains(&status) {
+        r
+    } else {
+        check_status(r).await?
     };
+    let j = parse_json(r).await?;
+    Ok((j, status, query_meta(&headers, start)?))
+}
 
-    Ok((
-        x.0,
-        QueryMeta {
-            last_index: x.1,
-            request_time: Instant::now() - start,
-        },
-    ))
+/// Opens a streaming endpoint, returning the response once its headers are
+/// in. `Config::timeout` does not apply, the body only ends when the caller
+/// drops the response. Never retried, neither compressed, as the body is
+/// handed out as it comes.
+pub async fn get_streaming(
+    path: &str,
+    config: &Config,
+    mut params: Vec<(String, String)>,
+    options: Option<&QueryOptions>,
+) -> Result<Response> {
+    add_query_options(&mut params, config, options);
+
+    let url_str = format!("{}{}", config.address, path);
+    let url =
+        Url::parse_with_params(&url_str, params.iter()).chain_err(|| "Failed to parse URL")?;
+    let r = dispatch(
+        add_token(config.http_client.get(url), config, query_token(options)),
+        config,
+    )
+    .await
+    .map_err(|f| f.error)?;
+    check_status(r).await
 }
 
-pub async fn delete<R: DeserializeOwned>(
+/// Like `get_streaming`, for a large body that ends rather than a feed: the
+/// response comes with its `QueryMeta`.
+pub async fn get_body_stream(
     path: &str,
     config: &Config,
-    params: HashMap<String, String>,
+    params: Vec<(String, String)>,
+    options: Option<&QueryOptions>,
+) -> Result<(Response, QueryMeta)> {
+    let start = Instant::now();
+    let r = get_streaming(path, config, params, options).await?;
+    let meta = query_meta(r.headers(), start)?;
+    Ok((r, meta))
+}
+
+/// Like `get`, but returns the raw response body instead of decoding JSON.
+pub async fn get_bytes(
+    path: &str,
+    config: &Config,
+    params: Vec<(String, String)>,
+    options: Option<&QueryOptions>,
+) -> Result<(Vec<u8>, QueryMeta)> {
+    let start = Instant::now();
+    let (body, headers) = get_raw(path, config, params, options).await?;
+    Ok((body.to_vec(), query_meta(&headers, start)?))
+}
+
+/// Like `get_bytes`, keeping every response header.
+pub async fn get_raw(
+    path: &str,
+    config: &Config,
+    mut params: Vec<(String, String)>,
+    options: Option<&QueryOptions>,
+) -> Result<(Bytes, HeaderMap)> {
+    add_query_options(&mut params, config, options);
+
+    let url_str = format!("{}{}", config.address, path);
+    let url =
+        Url::parse_with_params(&url_str, params.iter()).chain_err(|| "Failed to parse URL")?;
+    let r = send(
+        config.http_client.get(url),
+        config,
+        query_token(options),
+        true,
+    )
+    .await?;
+    let mut headers = r.headers().clone();
+    let body = read_body(check_status(r).await?).await?;
+    // Describe the body as handed out, not as it came over the wire
+    if is_gzip(&headers) {
+        headers.remove(CONTENT_ENCODING);
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+    }
+    Ok((body, headers))
+}
+
+pub async fn delete<T: Serialize, R: DeserializeOwned>(
+    path: &str,
+    body: Option<&T>,
+    config: &Config,
+    params: Vec<(String, String)>,
     options: Option<&WriteOptions>,
 ) -> Result<(R, WriteMeta)> {
-    let req = |http_client: &HttpClient, url: Url| -> RequestBuilder { http_client.delete(url) };
-    write_with_body(path, None as Option<&()>, config, params, options, req).await
+    let req = |http_client: &HttpClient, url: Url| -> RequestBuilder {
+        let builder = http_client.delete(url);
+        match body {
+            Some(b) => builder.json(b),
+            None => builder,
+        }
+    };
+    write(path, config, params, options, req).await
 }
 
-/*
-pub fn post<T: Serialize, R: DeserializeOwned>(path: &str,
-                                               body: Option<&T>,
-                                               config: &Config,
-                                               options: Option<&WriteOptions>)
-                                               -> Result<(R, WriteMeta)> {
-    let req = |http_client: &HttpClient, url: Url| -> RequestBuilder { http_client.post(url) };
-    write_with_body(path, body, config, options, req)
+pub async fn post<T: Serialize, R: DeserializeOwned>(
+    path: &str,
+    body: Option<&T>,
+    config: &Config,
+    params: Vec<(String, String)>,
+    options: Option<&WriteOptions>,
+) -> Result<(R, WriteMeta)> {
+    let req = |http_client: &HttpClient, url: Url| -> RequestBuilder {
+        let builder = http_client.post(url);
+        match body {
+            Some(b) => builder.json(b),
+            None => builder,
+        }
+    };
+    write(path, config, params, options, req).await
 }
-*/
+
 pub async fn put<T: Serialize, R: DeserializeOwned>(
     path: &str,
     body: Option<&T>,
     config: &Config,
-    params: HashMap<String, String>,
+    params: Vec<(String, String)>,
     options: Option<&WriteOptions>,
 ) -> Result<(R, WriteMeta)> {
-    let req = |http_client: &HttpClient, url: Url| -> RequestBuilder { http_client.put(url) };
-    write_with_body(path, body, config, params, options, req).await
+    let req = |http_client: &HttpClient, url: Url| -> RequestBuilder {
+        let builder = http_client.put(url);
+        match body {
+            Some(b) => builder.json(b),
+            None => builder,
+        }
+    };
+    write(path, config, params, options, req).await
 }
 
-async fn write_with_body<T: Serialize, R: DeserializeOwned, F>(
+/// Like `put`, but sends `body` verbatim instead of encoding it as JSON.
+pub async fn put_bytes<R: DeserializeOwned>(
     path: &str,
-    body: Option<&T>,
+    body: &[u8],
     config: &Config,
-    mut params: HashMap<String, String>,
+    params: Vec<(String, String)>,
+    options: Option<&WriteOptions>,
+) -> Result<(R, WriteMeta)> {
+    let req = |http_client: &HttpClient, url: Url| -> RequestBuilder {
+        http_client.put(url).body(body.to_vec())
+    };
+    write(path, config, params, options, req).await
+}
+
+async fn write<R: DeserializeOwned, F>(
+    path: &str,
+    config: &Config,
+    mut params: Vec<(String, String)>,
     options: Option<&WriteOptions>,
     req: F,
 ) -> Result<(R, WriteMeta)>
@@ -188,33 +665,27 @@ where
     F: Fn(&HttpClient, Url) -> RequestBuilder,
 {
     let start = Instant::now();
-    let datacenter: Option<&String> = options
-        .and_then(|o| o.datacenter.as_ref())
-        .or_else(|| config.datacenter.as_ref());
-
-    if let Some(dc) = datacenter {
-        params.insert(String::from("dc"), dc.to_owned());
-    }
+    add_scope(
+        &mut params,
+        config,
+        options.and_then(|o| o.datacenter.as_deref()),
+        options.and_then(|o| o.namespace.as_deref()),
+        options.and_then(|o| o.partition.as_deref()),
+    );
 
     let url_str = format!("{}{}", config.address, path);
     let url =
         Url::parse_with_params(&url_str, params.iter()).chain_err(|| "Failed to parse URL")?;
-    let builder = req(&config.http_client, url);
-    let builder = if let Some(b) = body {
-        builder.json(b)
-    } else {
-        builder
-    };
-    let builder = add_config_options(builder, &config);
-    let res = builder
-        .send()
-        .await
-        .chain_err(|| "HTTP request to consul failed")?;
-    let json = res.json().await.chain_err(|| "Failed to parse JSON")?;
+    let retry = options.is_some_and(|o| o.retry);
+    let token = options.and_then(|o| o.token.as_deref());
+    let res = send(req(&config.http_client, url), config, token, retry).await?;
+    let last_index = parse_header(res.headers(), "X-Consul-Index")?;
+    let json = parse_json(check_status(res).await?).await?;
 
     Ok((
         json,
         WriteMeta {
+            last_index,
             request_time: Instant::now() - start,
         },
     ))
@@ -0,0 +1,250 @@
+//! A semaphore over KV, letting at most `limit` holders in at a time.
+//!
+//! https://learn.hashicorp.com/tutorials/consul/distributed-semaphore
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinHandle;
+
+use crate::encoding::go_duration;
+use crate::errors::{Error, ErrorKind, Result, ResultExt};
+use crate::kv::{KVPair, KV};
+use crate::session::{Session, SessionEntry};
+use crate::watch::next_index;
+use crate::{Client, QueryOptions};
+
+/// Marks the keys the semaphore writes, as the Go client does, so that the
+/// two can share a prefix.
+const SEMAPHORE_FLAGS: u64 = 0xe0f6_9a2b_aa41_4de0;
+
+/// The key under the prefix holding the set of holders.
+const LOCK_KEY: &str = ".lock";
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SemaphoreLock {
+    Limit: u32,
+    /// The holders' sessions, a map for compatibility with the Go client.
+    Holders: HashMap<String, bool>,
+}
+
+/// One contender for a semaphore, following Consul's documented pattern:
+/// each contender holds a key under the prefix on its own session, and the
+/// holders are recorded in `<prefix>/.lock`, updated with check-and-set.
+/// Holders whose session is gone are dropped from the set by whichever
+/// contender next updates it.
+///
+/// The session is renewed in the background until `close`, or until the
+/// semaphore is dropped, after which it expires with its TTL. Once the
+/// session is invalidated, or cannot be renewed for a whole TTL, the slot is
+/// lost and `acquire` fails with `ErrorKind::SessionLost`.
+pub struct Semaphore {
+    client: Client,
+    prefix: String,
+    limit: u32,
+    session: String,
+    /// Why the session was lost, set once by `keep_alive`.
+    lost: Arc<Mutex<Option<String>>>,
+    renew: JoinHandle<()>,
+}
+
+impl Semaphore {
+    /// Creates a session with the given TTL and registers as a contender
+    /// for the semaphore at `prefix`. All contenders must agree on `limit`.
+    pub async fn new(
+        client: &Client,
+        prefix: &str,
+        limit: u32,
+        ttl: Duration,
+    ) -> Result<Semaphore> {
+        if limit == 0 {
+            return Err(Error::from("A semaphore needs a limit of at least 1"));
+        }
+        let prefix = prefix.trim_end_matches('/').to_owned();
+        let entry = SessionEntry {
+            Name: Some(format!("semaphore on {}", prefix)),
+            TTL: Some(go_duration::format(ttl)),
+            Behavior: Some(String::from("delete")),
+            ..Default::default()
+        };
+        let (session, _) = client.create(&entry, None).await?;
+        let session = session.ID;
+
+        let contender = KVPair {
+            Key: format!("{}/{}", prefix, session),
+            Flags: Some(SEMAPHORE_FLAGS),
+            Session: Some(session.clone()),
+            ..Default::default()
+        };
+        if !client.acquire(&contender, None).await?.0 {
+            return Err(Error::from("Failed to register as a semaphore contender"));
+        }
+        let lost = Arc::new(Mutex::new(None));
+        let renew = tokio::spawn(keep_alive(
+            client.clone(),
+            session.clone(),
+            ttl,
+            lost.clone(),
+        ));
+        Ok(Semaphore {
+            client: client.clone(),
+            prefix,
+            limit,
+            session,
+            lost,
+            renew,
+        })
+    }
+
+    /// The ID of this contender's session.
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    /// Waits up to `timeout` for a slot, returning whether one was taken.
+    /// Taking a slot already held succeeds right away.
+    pub async fn acquire(&self, timeout: Duration) -> Result<bool> {
+        let deadline = Instant::now() + timeout;
+        let mut index = None;
+        loop {
+            if let Some(cause) = self.lost.lock().unwrap().clone() {
+                return Err(ErrorKind::SessionLost(self.session.clone(), cause).into());
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if index.is_some() && remaining.is_zero() {
+                return Ok(false);
+            }
+            let options = QueryOptions {
+                wait_index: index,
+                wait_time: Some(remaining),
+                ..Default::default()
+            };
+            let prefix = format!("{}/", self.prefix);
+            let (pairs, meta) = KV::list(&self.client, &prefix, Some(&options)).await?;
+            index = Some(next_index(index, &meta));
+
+            let (mut lock, lock_index) = self.read_lock(&pairs)?;
+            if lock.Holders.contains_key(&self.session) {
+                return Ok(true);
+            }
+            // Sessions that went away no longer hold a slot
+            let live: Vec<&str> = pairs
+                .iter()
+                .filter(|p| p.Session.is_some())
+                .filter_map(|p| p.Key.strip_prefix(&prefix))
+                .collect();
+            lock.Holders
+                .retain(|holder, _| live.contains(&holder.as_str()));
+            if (lock.Holders.len() as u32) < self.limit {
+                lock.Holders.insert(self.session.clone(), true);
+                if self.write_lock(&lock, lock_index).await? {
+                    return Ok(true);
+                }
+                // Someone else updated the holders, look again right away
+                index = None;
+            }
+        }
+    }
+
+    /// Gives the slot back, if held.
+    pub async fn release(&self) -> Result<()> {
+        loop {
+            let prefix = format!("{}/", self.prefix);
+            let (pairs, _) = KV::list(&self.client, &prefix, None).await?;
+            let (mut lock, lock_index) = self.read_lock(&pairs)?;
+            if lock.Holders.remove(&self.session).is_none() {
+                return Ok(());
+            }
+            if self.write_lock(&lock, lock_index).await? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Releases the slot and destroys the session, removing this contender.
+    pub async fn close(self) -> Result<()> {
+        self.renew.abort();
+        self.release().await?;
+        self.client.destroy(&self.session, None).await.map(|_| ())
+    }
+
+    fn read_lock(&self, pairs: &[KVPair]) -> Result<(SemaphoreLock, u64)> {
+        let key = format!("{}/{}", self.prefix, LOCK_KEY);
+        let pair = match pairs.iter().find(|p| p.Key == key) {
+            Some(pair) => pair,
+            None => {
+                let lock = SemaphoreLock {
+                    Limit: self.limit,
+                    ..Default::default()
+                };
+                return Ok((lock, 0));
+            }
+        };
+        let lock: SemaphoreLock = serde_json::from_slice(pair.Value.as_deref().unwrap_or_default())
+            .chain_err(|| format!("Failed to parse the semaphore lock at {}", key))?;
+        if lock.Limit != self.limit {
+            return Err(Error::from(format!(
+                "The semaphore at {} has a limit of {}, not {}",
+                self.prefix, lock.Limit, self.limit
+            )));
+        }
+        Ok((lock, pair.ModifyIndex.unwrap_or_default()))
+    }
+
+    async fn write_lock(&self, lock: &SemaphoreLock, index: u64) -> Result<bool> {
+        let pair = KVPair {
+            Key: format!("{}/{}", self.prefix, LOCK_KEY),
+            Value: Some(
+                serde_json::to_vec(lock).chain_err(|| "Failed to encode the semaphore lock")?,
+            ),
+            Flags: Some(SEMAPHORE_FLAGS),
+            ..Default::default()
+        };
+        self.client
+            .cas(&pair, index, None)
+            .await
+            .map(|(written, _)| written)
+    }
+}
+
+impl Drop for Semaphore {
+    fn drop(&mut self) {
+        self.renew.abort();
+    }
+}
+
+/// Renews the session at half its TTL, and retries failed renewals sooner,
+/// until the session is gone.
+async fn keep_alive(
+    client: Client,
+    session: String,
+    ttl: Duration,
+    lost: Arc<Mutex<Option<String>>>,
+) {
+    let mut renewed = Instant::now();
+    let mut delay = ttl / 2;
+    loop {
+        tokio::time::sleep(delay).await;
+        match client.renew(&session, None).await {
+            Ok(_) => {
+                renewed = Instant::now();
+                delay = ttl / 2;
+            }
+            Err(e) => {
+                if let ErrorKind::Consul(404, _) = e.kind() {
+                    // The session is gone, and the slot with it
+                    *lost.lock().unwrap() = Some(String::from("invalidated"));
+                    return;
+                }
+                if renewed.elapsed() >= ttl {
+                    // Consul will have expired the session by now
+                    *lost.lock().unwrap() = Some(e.to_string());
+                    return;
+                }
+                delay = (ttl / 10).min(Duration::from_secs(1));
+            }
+        }
+    }
+}
@@ -1,9 +1,8 @@
 use async_trait::async_trait;
-use std::collections::HashMap;
 
 use crate::errors::Result;
 use crate::request::{get, put};
-use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use crate::{Client, GoDuration, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
 
 #[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
@@ -19,7 +18,7 @@ pub struct SessionEntry {
     pub ID: Option<String>,
     pub Name: Option<String>,
     pub Node: Option<String>,
-    pub LockDelay: Option<u64>, //TODO: Change this to a Durations
+    pub LockDelay: Option<GoDuration>,
     pub Behavior: Option<String>,
     pub Checks: Option<Vec<String>>,
     pub TTL: Option<String>,
@@ -31,7 +30,7 @@ pub trait Session {
         &self,
         session: &SessionEntry,
         options: Option<&WriteOptions>,
-    ) -> Result<(SessionEntry, WriteMeta)>;
+    ) -> Result<(SessionID, WriteMeta)>;
     async fn destroy(&self, id: &str, options: Option<&WriteOptions>) -> Result<(bool, WriteMeta)>;
     async fn info(
         &self,
@@ -57,12 +56,12 @@ impl Session for Client {
         &self,
         session: &SessionEntry,
         options: Option<&WriteOptions>,
-    ) -> Result<(SessionEntry, WriteMeta)> {
+    ) -> Result<(SessionID, WriteMeta)> {
         put(
             "/v1/session/create",
             Some(session),
             &self.config,
-            HashMap::new(),
+            Vec::new(),
             options,
         )
         .await
@@ -73,7 +72,7 @@ impl Session for Client {
             &path,
             None as Option<&()>,
             &self.config,
-            HashMap::new(),
+            Vec::new(),
             options,
         )
         .await
@@ -84,10 +83,10 @@ impl Session for Client {
         options: Option<&QueryOptions>,
     ) -> Result<(Vec<SessionEntry>, QueryMeta)> {
         let path = format!("/v1/session/info/{}", id);
-        get(&path, &self.config, HashMap::new(), options).await
+        get(&path, &self.config, Vec::new(), options).await
     }
     async fn list(&self, options: Option<&QueryOptions>) -> Result<(Vec<SessionEntry>, QueryMeta)> {
-        get("/v1/session/list", &self.config, HashMap::new(), options).await
+        get("/v1/session/list", &self.config, Vec::new(), options).await
     }
     async fn node(
         &self,
@@ -95,7 +94,7 @@ impl Session for Client {
         options: Option<&QueryOptions>,
     ) -> Result<(Vec<SessionEntry>, QueryMeta)> {
         let path = format!("/v1/session/node/{}", node);
-        get(&path, &self.config, HashMap::new(), options).await
+        get(&path, &self.config, Vec::new(), options).await
     }
 
     async fn renew(
@@ -108,7 +107,7 @@ impl Session for Client {
             &path,
             None as Option<&()>,
             &self.config,
-            HashMap::new(),
+            Vec::new(),
             options,
         )
         .await
@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::{self, BoxStream, StreamExt};
+
+use crate::errors::{Error, Result};
+use crate::request::{get_body_stream, get_bytes, put_bytes};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+
+#[async_trait]
+pub trait Snapshot {
+    async fn save(&self, q: Option<&QueryOptions>) -> Result<(Vec<u8>, QueryMeta)>;
+    async fn save_stream(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(BoxStream<'static, Result<Bytes>>, QueryMeta)>;
+    async fn restore(&self, data: &[u8], q: Option<&WriteOptions>) -> Result<WriteMeta>;
+}
+
+#[async_trait]
+impl Snapshot for Client {
+    /// https://www.consul.io/api/snapshot.html#generate-snapshot
+    ///
+    /// Returns the gzipped snapshot archive, held in memory, along with the
+    /// Raft index it was taken at in `QueryMeta::last_index`. Pass
+    /// `ConsistencyMode::Stale` to let a follower take the snapshot. See
+    /// `save_stream` for clusters whose snapshots are too large to buffer.
+    async fn save(&self, q: Option<&QueryOptions>) -> Result<(Vec<u8>, QueryMeta)> {
+        get_bytes("/v1/snapshot", &self.config, Vec::new(), q).await
+    }
+
+    /// https://www.consul.io/api/snapshot.html#generate-snapshot
+    ///
+    /// Like `save`, yielding the archive in chunks as Consul sends it, e.g.
+    /// to write it to a file. `Config::timeout` and `Config::retry` do not
+    /// apply; an error ends the stream, leaving the archive incomplete.
+    async fn save_stream(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(BoxStream<'static, Result<Bytes>>, QueryMeta)> {
+        let (response, meta) = get_body_stream("/v1/snapshot", &self.config, Vec::new(), q).await?;
+        let chunks = stream::unfold(Some(response), |response| async move {
+            let mut response = response?;
+            match response.chunk().await {
+                Ok(Some(chunk)) => Some((Ok(chunk), Some(response))),
+                Ok(None) => None,
+                Err(e) => Some((Err(Error::with_chain(e, "Failed to read snapshot")), None)),
+            }
+        });
+        Ok((chunks.boxed(), meta))
+    }
+
+    /// https://www.consul.io/api/snapshot.html#restore-snapshot
+    ///
+    /// Replaces the whole cluster state with the one in `data`, an archive
+    /// produced by `save`.
+    async fn restore(&self, data: &[u8], q: Option<&WriteOptions>) -> Result<WriteMeta> {
+        put_bytes("/v1/snapshot", data, &self.config, Vec::new(), q)
+            .await
+            .map(|x: (Option<()>, WriteMeta)| x.1)
+    }
+}
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+
+use crate::errors::Result;
+use crate::request::get;
+use crate::Client;
+
+#[async_trait]
+pub trait Status {
+    async fn leader(&self) -> Result<String>;
+    async fn peers(&self) -> Result<Vec<String>>;
+}
+
+#[async_trait]
+impl Status for Client {
+    /// https://www.consul.io/api/status.html#get-raft-leader
+    ///
+    /// Returns the Raft address (`host:port`) of the current leader, or an
+    /// empty string while the cluster has no leader, e.g. during an election.
+    async fn leader(&self) -> Result<String> {
+        get("/v1/status/leader", &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+
+    /// https://www.consul.io/api/status.html#list-raft-peers
+    async fn peers(&self) -> Result<Vec<String>> {
+        get("/v1/status/peers", &self.config, Vec::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+}
@@ -0,0 +1,135 @@
+//! TLS settings for agents serving their HTTP API over https.
+
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use reqwest::{Certificate, ClientBuilder as HttpClientBuilder, Identity};
+
+use crate::errors::{Result, ResultExt};
+use crate::Redacted;
+
+#[derive(Clone)]
+enum Pem {
+    Bytes(Vec<u8>),
+    Path(PathBuf),
+}
+
+impl fmt::Debug for Pem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // May hold a private key
+            Pem::Bytes(_) => f.debug_tuple("Bytes").field(&Redacted).finish(),
+            Pem::Path(path) => f.debug_tuple("Path").field(path).finish(),
+        }
+    }
+}
+
+impl Pem {
+    fn read(&self, what: &str) -> Result<Vec<u8>> {
+        match self {
+            Pem::Bytes(bytes) => Ok(bytes.clone()),
+            Pem::Path(path) => {
+                fs::read(path).chain_err(|| format!("Failed to read {} {:?}", what, path))
+            }
+        }
+    }
+}
+
+/// How the client authenticates the agent, and itself to an agent with
+/// `verify_incoming` set, passed to `ClientBuilder::tls`:
+///
+/// ```no_run
+/// # fn main() -> consul::errors::Result<()> {
+/// use consul::tls::TlsConfig;
+/// let client = consul::ClientBuilder::new()
+///     .address("https://consul.example.com:8501")
+///     .tls(
+///         TlsConfig::new()
+///             .ca_cert_path("/etc/consul/ca.pem")
+///             .client_cert_path("/etc/consul/client.pem", "/etc/consul/client-key.pem"),
+///     )
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    ca_cert: Option<Pem>,
+    client_cert: Option<(Pem, Pem)>,
+    verify_hostname: bool,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        TlsConfig {
+            ca_cert: None,
+            client_cert: None,
+            verify_hostname: true,
+        }
+    }
+}
+
+impl TlsConfig {
+    /// Trusts the system's root certificates and verifies hostnames.
+    pub fn new() -> Self {
+        TlsConfig::default()
+    }
+
+    /// Also trusts the certificates of this PEM bundle, typically the CA
+    /// that signed the agents' certificates (`CONSUL_CACERT`).
+    pub fn ca_cert_pem(mut self, pem: &[u8]) -> Self {
+        self.ca_cert = Some(Pem::Bytes(pem.to_vec()));
+        self
+    }
+
+    /// Like `ca_cert_pem`, reading the bundle when the client is built.
+    pub fn ca_cert_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.ca_cert = Some(Pem::Path(path.into()));
+        self
+    }
+
+    /// The certificate and PKCS#8 key the client presents to the agent. A
+    /// SEC1 key, such as the ones `consul tls cert create` writes, converts
+    /// with `openssl pkcs8 -topk8 -nocrypt`.
+    pub fn client_cert_pem(mut self, cert: &[u8], key: &[u8]) -> Self {
+        self.client_cert = Some((Pem::Bytes(cert.to_vec()), Pem::Bytes(key.to_vec())));
+        self
+    }
+
+    /// Like `client_cert_pem`, reading the files when the client is built.
+    pub fn client_cert_path<P: Into<PathBuf>>(mut self, cert: P, key: P) -> Self {
+        self.client_cert = Some((Pem::Path(cert.into()), Pem::Path(key.into())));
+        self
+    }
+
+    /// Whether the agent's certificate must match the address' host, true
+    /// by default. Turning it off still verifies the certificate chain.
+    pub fn verify_hostname(mut self, verify: bool) -> Self {
+        self.verify_hostname = verify;
+        self
+    }
+
+    pub(crate) fn apply(&self, builder: HttpClientBuilder) -> Result<HttpClientBuilder> {
+        let mut builder = builder.danger_accept_invalid_hostnames(!self.verify_hostname);
+        if let Some(ca_cert) = &self.ca_cert {
+            let pem = ca_cert.read("CA certificate")?;
+            let certs =
+                Certificate::from_pem_bundle(&pem).chain_err(|| "Invalid CA certificate")?;
+            if certs.is_empty() {
+                return Err("Invalid CA certificate, no certificate found".into());
+            }
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        if let Some((cert, key)) = &self.client_cert {
+            let cert = cert.read("client certificate")?;
+            let key = key.read("client key")?;
+            let identity = Identity::from_pkcs8_pem(&cert, &key)
+                .chain_err(|| "Invalid client certificate or key")?;
+            builder = builder.identity(identity);
+        }
+        Ok(builder)
+    }
+}
@@ -0,0 +1,99 @@
+use async_trait::async_trait;
+
+use crate::errors::{ErrorKind, Result, ResultExt};
+use crate::kv::KVPair;
+use crate::request::put;
+use crate::{Client, WriteOptions};
+
+/// https://www.consul.io/api/txn.html#kv-operations
+#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum KVOp {
+    Set,
+    /// Like `Set`, only if the key's `ModifyIndex` equals `Index`.
+    Cas,
+    Lock,
+    Unlock,
+    #[default]
+    Get,
+    GetTree,
+    CheckIndex,
+    CheckSession,
+    CheckNotExists,
+    Delete,
+    DeleteTree,
+    /// Like `Delete`, only if the key's `ModifyIndex` equals `Index`.
+    DeleteCas,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct KVTxnOp {
+    pub Verb: KVOp,
+    pub Key: String,
+    #[serde(with = "crate::encoding::base64_bytes")]
+    pub Value: Vec<u8>,
+    pub Flags: u64,
+    pub Index: u64,
+    pub Session: String,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub enum TxnOp {
+    KV(KVTxnOp),
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub enum TxnResult {
+    KV(KVPair),
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct TxnError {
+    /// Position in the submitted operations of the one that failed.
+    pub OpIndex: usize,
+    pub What: String,
+}
+
+/// The outcome of a transaction: either it was applied and `Results` has
+/// one entry per operation that returns data, or it was rolled back and
+/// `Errors` tells which operations failed.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct TxnResponse {
+    pub Results: Option<Vec<TxnResult>>,
+    pub Errors: Option<Vec<TxnError>>,
+}
+
+impl TxnResponse {
+    pub fn is_committed(&self) -> bool {
+        self.Errors.as_ref().map_or(true, |e| e.is_empty())
+    }
+}
+
+#[async_trait]
+pub trait Txn {
+    async fn run(&self, ops: Vec<TxnOp>, q: Option<&WriteOptions>) -> Result<TxnResponse>;
+}
+
+#[async_trait]
+impl Txn for Client {
+    /// https://www.consul.io/api/txn.html#create-transaction
+    ///
+    /// Applies `ops` atomically. A transaction rolled back because one of
+    /// its operations failed, e.g. a `Cas` on a stale index, is not an
+    /// error: check `TxnResponse::is_committed`.
+    async fn run(&self, ops: Vec<TxnOp>, q: Option<&WriteOptions>) -> Result<TxnResponse> {
+        match put("/v1/txn", Some(&ops), &self.config, Vec::new(), q).await {
+            Ok(x) => Ok(x.0),
+            // Consul answers 409 along with the failed operations
+            Err(e) => match e.kind() {
+                ErrorKind::Consul(409, body) => {
+                    serde_json::from_str(body).chain_err(|| "Failed to parse JSON response")
+                }
+                _ => Err(e),
+            },
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Streams built on blocking queries, yielding a new item whenever the
+//! watched result changes.
+
+use std::future::Future;
+
+use futures::stream::{self, BoxStream, StreamExt};
+use reqwest::StatusCode;
+
+use crate::errors::Result;
+use crate::health::{Health, ServiceEntry};
+use crate::kv::KVPair;
+use crate::request::get_with_status;
+use crate::{Client, Config, QueryMeta, QueryOptions};
+
+pub trait Watch {
+    fn watch_kv(&self, key: &str) -> BoxStream<'static, Result<Option<KVPair>>>;
+    fn watch_service(
+        &self,
+        service: &str,
+        passing_only: bool,
+    ) -> BoxStream<'static, Result<Vec<ServiceEntry>>>;
+}
+
+impl Watch for Client {
+    /// Yields the current state of `key`, then its new state each time it
+    /// is written or deleted; `None` while the key does not exist.
+    ///
+    /// Each poll blocks for up to `Config::wait_time`, Consul's 5 minutes by
+    /// default, so keep `Config::timeout` above it. An error is yielded as
+    /// is, polling the stream again retries the query after a delay growing
+    /// with each error in a row, following `Config::retry`'s backoff, or the
+    /// default `RetryPolicy`'s.
+    fn watch_kv(&self, key: &str) -> BoxStream<'static, Result<Option<KVPair>>> {
+        let client = self.clone();
+        let path = format!("/v1/kv/{}", key);
+        watch(&self.config, move |options| {
+            let client = client.clone();
+            let path = path.clone();
+            async move {
+                // A missing key is a 404 with an empty body
+                let (pairs, _, meta): (Option<Vec<KVPair>>, _, _) = get_with_status(
+                    &path,
+                    &client.config,
+                    Vec::new(),
+                    Some(&options),
+                    &[StatusCode::NOT_FOUND],
+                )
+                .await?;
+                Ok((pairs.and_then(|p| p.into_iter().next()), meta))
+            }
+        })
+    }
+
+    /// Yields the instances of `service`, as `Health::service` lists them,
+    /// then the updated list each time an instance comes, goes or changes
+    /// health. Index bumps that leave the list unchanged are skipped.
+    fn watch_service(
+        &self,
+        service: &str,
+        passing_only: bool,
+    ) -> BoxStream<'static, Result<Vec<ServiceEntry>>> {
+        let client = self.clone();
+        let service = service.to_owned();
+        watch(&self.config, move |options| {
+            let client = client.clone();
+            let service = service.clone();
+            async move {
+                client
+                    .service(&service, None, passing_only, Some(&options))
+                    .await
+            }
+        })
+    }
+}
+
+/// Repeats the blocking query `fetch`, feeding it the index of the previous
+/// answer, and yields its result whenever it differs from the last one.
+/// After an error, the next query waits for the backoff of
+/// `Config::retry`, so that a consumer polling on through errors does not
+/// hammer an agent that is down.
+pub(crate) fn watch<T, F, Fut>(config: &Config, fetch: F) -> BoxStream<'static, Result<T>>
+where
+    T: PartialEq + Clone + Send + 'static,
+    F: FnMut(QueryOptions) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(T, QueryMeta)>> + Send + 'static,
+{
+    let wait_time = config.wait_time;
+    let backoff = config.retry.clone().unwrap_or_default();
+    // The errors in a row so far
+    let state: (F, Option<u64>, Option<T>, u32) = (fetch, None, None, 0);
+    stream::unfold(state, move |(mut fetch, mut index, mut last, errors)| {
+        let backoff = backoff.clone();
+        async move {
+            if errors > 0 {
+                tokio::time::sleep(backoff.backoff(errors - 1)).await;
+            }
+            loop {
+                let options = QueryOptions {
+                    wait_index: index,
+                    wait_time,
+                    ..Default::default()
+                };
+                let (value, meta) = match fetch(options).await {
+                    Ok(answer) => answer,
+                    Err(e) => return Some((Err(e), (fetch, index, last, errors + 1))),
+                };
+                index = Some(next_index(index, &meta));
+                if last.as_ref() != Some(&value) {
+                    last = Some(value.clone());
+                    return Some((Ok(value), (fetch, index, last, 0)));
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+/// https://www.consul.io/api/features/blocking#implementation-details
+///
+/// An index going backwards, e.g. after a snapshot restore, restarts from 0
+/// rather than blocking on an index that may never be reached again, and an
+/// index of 0 is never waited on as it would not block.
+pub(crate) fn next_index(last: Option<u64>, meta: &QueryMeta) -> u64 {
+    let index = meta.last_index.unwrap_or(0);
+    match last {
+        Some(last) if index < last => 0,
+        _ => index.max(1),
+    }
+}
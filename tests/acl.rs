@@ -0,0 +1,334 @@
+extern crate consul;
+pub mod common;
+
+use common::set_up;
+use consul::acl::{ACLToken, ACL};
+
+#[tokio::test]
+async fn acl_token_test() {
+    let client = set_up();
+
+    let token = ACLToken {
+        Description: String::from("acl token test"),
+        ..Default::default()
+    };
+    let (created, _) = client.token_create(&token, None).await.unwrap();
+    assert!(!created.AccessorID.is_empty());
+    assert!(!created.SecretID.is_empty());
+
+    let (read, _) = client.token_read(&created.AccessorID, None).await.unwrap();
+    assert_eq!(read.Description, "acl token test");
+    assert_eq!(read.SecretID, created.SecretID);
+
+    let updated = ACLToken {
+        Description: String::from("acl token test, updated"),
+        ..created.clone()
+    };
+    let (updated, _) = client.token_update(&updated, None).await.unwrap();
+    assert_eq!(updated.Description, "acl token test, updated");
+
+    let (tokens, _) = client.token_list(None).await.unwrap();
+    assert!(tokens.iter().any(|t| t.AccessorID == created.AccessorID));
+
+    assert!(
+        client
+            .token_delete(&created.AccessorID, None)
+            .await
+            .unwrap()
+            .0
+    );
+    let (tokens, _) = client.token_list(None).await.unwrap();
+    assert!(tokens.iter().all(|t| t.AccessorID != created.AccessorID));
+}
+
+#[tokio::test]
+async fn acl_policy_test() {
+    use consul::acl::{ACLLink, ACLPolicy};
+    let client = set_up();
+
+    let policy = ACLPolicy {
+        Name: String::from("acl-policy-test"),
+        Description: String::from("acl policy test"),
+        Rules: String::from(r#"key_prefix "acl-policy-test/" { policy = "read" }"#),
+        ..Default::default()
+    };
+    let (created, _) = client.policy_create(&policy, None).await.unwrap();
+    assert!(!created.ID.is_empty());
+
+    let (read, _) = client.policy_read(&created.ID, None).await.unwrap();
+    assert_eq!(read.Name, "acl-policy-test");
+    assert_eq!(read.Rules, policy.Rules);
+
+    let updated = ACLPolicy {
+        Description: String::from("acl policy test, updated"),
+        ..read
+    };
+    let (updated, _) = client.policy_update(&updated, None).await.unwrap();
+    assert_eq!(updated.Description, "acl policy test, updated");
+
+    // The generated ID is what tokens use to reference the policy
+    let token = ACLToken {
+        Policies: Some(vec![ACLLink {
+            ID: Some(created.ID.clone()),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    };
+    let (token, _) = client.token_create(&token, None).await.unwrap();
+    assert_eq!(
+        token.Policies.unwrap()[0].Name.as_deref(),
+        Some("acl-policy-test")
+    );
+    client.token_delete(&token.AccessorID, None).await.unwrap();
+
+    let (policies, _) = client.policy_list(None).await.unwrap();
+    assert!(policies.iter().any(|p| p.ID == created.ID));
+
+    assert!(client.policy_delete(&created.ID, None).await.unwrap().0);
+    let (policies, _) = client.policy_list(None).await.unwrap();
+    assert!(policies.iter().all(|p| p.ID != created.ID));
+}
+
+#[tokio::test]
+async fn acl_bootstrap_test() {
+    use consul::errors::ErrorKind;
+    let client = set_up();
+
+    // The first run against a fresh agent bootstraps it, any later call is
+    // rejected
+    match client.bootstrap().await {
+        Ok(token) => assert!(!token.SecretID.is_empty()),
+        Err(e) => match e.kind() {
+            ErrorKind::AlreadyBootstrapped => {}
+            other => panic!("unexpected error: {}", other),
+        },
+    }
+    match client.bootstrap().await {
+        Err(e) => match e.kind() {
+            ErrorKind::AlreadyBootstrapped => {}
+            other => panic!("unexpected error: {}", other),
+        },
+        Ok(_) => panic!("a second bootstrap must be rejected"),
+    }
+}
+
+#[tokio::test]
+async fn acl_login_logout_test() {
+    use consul::errors::ErrorKind;
+    use std::collections::HashMap;
+    let client = set_up();
+
+    match client
+        .login("non-existing-auth-method", "bearer", HashMap::new())
+        .await
+    {
+        Err(e) => match e.kind() {
+            ErrorKind::Consul(status, _) => assert!(*status >= 400),
+            other => panic!("unexpected error: {}", other),
+        },
+        Ok(_) => panic!("login through an unknown auth method must fail"),
+    }
+
+    // Only tokens issued by a login can be logged out
+    let (token, _) = client
+        .token_create(&ACLToken::default(), None)
+        .await
+        .unwrap();
+    assert!(client.logout(&token.SecretID).await.is_err());
+    client.token_delete(&token.AccessorID, None).await.unwrap();
+}
+
+#[tokio::test]
+async fn acl_role_test() {
+    use consul::acl::{ACLLink, ACLPolicy, ACLRole, ACLServiceIdentity};
+    let client = set_up();
+
+    let policy = ACLPolicy {
+        Name: String::from("acl-role-test"),
+        Rules: String::from(r#"key_prefix "acl-role-test/" { policy = "read" }"#),
+        ..Default::default()
+    };
+    let (policy, _) = client.policy_create(&policy, None).await.unwrap();
+
+    let role = ACLRole {
+        Name: String::from("acl-role-test"),
+        Description: String::from("acl role test"),
+        Policies: Some(vec![ACLLink {
+            ID: Some(policy.ID.clone()),
+            ..Default::default()
+        }]),
+        ServiceIdentities: Some(vec![ACLServiceIdentity {
+            ServiceName: String::from("web"),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    };
+    let (created, _) = client.role_create(&role, None).await.unwrap();
+    assert!(!created.ID.is_empty());
+
+    let (read, _) = client.role_read(&created.ID, None).await.unwrap();
+    assert_eq!(read.Name, "acl-role-test");
+    assert_eq!(
+        read.Policies.as_ref().unwrap()[0].Name.as_deref(),
+        Some("acl-role-test")
+    );
+    let (by_name, _) = client
+        .role_read_by_name("acl-role-test", None)
+        .await
+        .unwrap();
+    assert_eq!(by_name.ID, created.ID);
+
+    let updated = ACLRole {
+        Description: String::from("acl role test, updated"),
+        ..read
+    };
+    let (updated, _) = client.role_update(&updated, None).await.unwrap();
+    assert_eq!(updated.Description, "acl role test, updated");
+
+    let (roles, _) = client.role_list(None).await.unwrap();
+    assert!(roles.iter().any(|r| r.ID == created.ID));
+
+    assert!(client.role_delete(&created.ID, None).await.unwrap().0);
+    let (roles, _) = client.role_list(None).await.unwrap();
+    assert!(roles.iter().all(|r| r.ID != created.ID));
+    client.policy_delete(&policy.ID, None).await.unwrap();
+}
+
+#[tokio::test]
+async fn acl_auth_method_test() {
+    use consul::acl::ACLAuthMethod;
+    use serde_json::json;
+    let client = set_up();
+
+    let mut method = ACLAuthMethod {
+        Name: String::from("acl-auth-method-test"),
+        Type: String::from("jwt"),
+        Description: String::from("acl auth method test"),
+        TokenLocality: String::from("global"),
+        ..Default::default()
+    };
+    method.Config.insert(
+        String::from("JWKSURL"),
+        json!("https://example.com/.well-known/jwks.json"),
+    );
+    let (created, _) = client.auth_method_create(&method, None).await.unwrap();
+    assert_eq!(created.Type, "jwt");
+
+    let (read, _) = client
+        .auth_method_read("acl-auth-method-test", None)
+        .await
+        .unwrap();
+    assert_eq!(read.TokenLocality, "global");
+    assert_eq!(read.Config["JWKSURL"], method.Config["JWKSURL"]);
+
+    let updated = ACLAuthMethod {
+        Description: String::from("acl auth method test, updated"),
+        ..read
+    };
+    let (updated, _) = client.auth_method_update(&updated, None).await.unwrap();
+    assert_eq!(updated.Description, "acl auth method test, updated");
+
+    let (methods, _) = client.auth_method_list(None).await.unwrap();
+    assert!(methods.iter().any(|m| m.Name == "acl-auth-method-test"));
+
+    assert!(
+        client
+            .auth_method_delete("acl-auth-method-test", None)
+            .await
+            .unwrap()
+            .0
+    );
+    let (methods, _) = client.auth_method_list(None).await.unwrap();
+    assert!(methods.iter().all(|m| m.Name != "acl-auth-method-test"));
+}
+
+#[tokio::test]
+async fn acl_binding_rule_test() {
+    use consul::acl::{ACLAuthMethod, ACLBindingRule};
+    use serde_json::json;
+    let client = set_up();
+
+    let mut method = ACLAuthMethod {
+        Name: String::from("acl-binding-rule-test"),
+        Type: String::from("jwt"),
+        ..Default::default()
+    };
+    method.Config.insert(
+        String::from("JWKSURL"),
+        json!("https://example.com/.well-known/jwks.json"),
+    );
+    client.auth_method_create(&method, None).await.unwrap();
+
+    let rule = ACLBindingRule {
+        AuthMethod: String::from("acl-binding-rule-test"),
+        Selector: String::from("value.team == web"),
+        BindType: String::from("service"),
+        BindName: String::from("web"),
+        ..Default::default()
+    };
+    let (created, _) = client.binding_rule_create(&rule, None).await.unwrap();
+    assert!(!created.ID.is_empty());
+
+    let (read, _) = client.binding_rule_read(&created.ID, None).await.unwrap();
+    assert_eq!(read.BindName, "web");
+    assert_eq!(read.Selector, rule.Selector);
+
+    let updated = ACLBindingRule {
+        BindName: String::from("web-v2"),
+        ..read
+    };
+    let (updated, _) = client.binding_rule_update(&updated, None).await.unwrap();
+    assert_eq!(updated.BindName, "web-v2");
+
+    let (rules, _) = client
+        .binding_rule_list(Some("acl-binding-rule-test"), None)
+        .await
+        .unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].ID, created.ID);
+
+    assert!(
+        client
+            .binding_rule_delete(&created.ID, None)
+            .await
+            .unwrap()
+            .0
+    );
+    let (rules, _) = client
+        .binding_rule_list(Some("acl-binding-rule-test"), None)
+        .await
+        .unwrap();
+    assert!(rules.is_empty());
+    client
+        .auth_method_delete("acl-binding-rule-test", None)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn acl_token_debug_test() {
+    let token = ACLToken {
+        AccessorID: String::from("6a1253d2-1785-24fd-91c2-f8e78c745511"),
+        SecretID: String::from("45a3bd52-07c7-47a4-52fd-0745e0cfe967"),
+        ..Default::default()
+    };
+    let debug = format!("{:?}", token);
+    assert!(debug.contains("6a1253d2-1785-24fd-91c2-f8e78c745511"));
+    assert!(!debug.contains("45a3bd52-07c7-47a4-52fd-0745e0cfe967"));
+}
+
+#[test]
+fn acl_auth_method_debug_redacts_config_test() {
+    use consul::acl::ACLAuthMethod;
+    let mut method = ACLAuthMethod {
+        Name: String::from("oidc"),
+        Type: String::from("oidc"),
+        ..Default::default()
+    };
+    method
+        .Config
+        .insert(String::from("OIDCClientSecret"), "hunter2".into());
+    let debug = format!("{:?}", method);
+    assert!(debug.contains("OIDCClientSecret"));
+    assert!(!debug.contains("hunter2"));
+}
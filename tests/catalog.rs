@@ -1,5 +1,8 @@
 extern crate consul;
-use consul::{Client, Config};
+pub mod common;
+
+use common::{ok, serve};
+use consul::{Client, ClientBuilder, Config};
 
 #[tokio::test]
 async fn ds_test() {
@@ -22,3 +25,377 @@ async fn ds_services_test() {
         Some(val) => assert_eq!(val.len(), 0), // consul has no tags
     }
 }
+
+#[tokio::test]
+async fn register_deregister_test() {
+    use consul::agent::AgentService;
+    use consul::catalog::{Catalog, CatalogDeregistration, CatalogRegistration};
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let reg = CatalogRegistration {
+        Node: String::from("catalog-test-node"),
+        Address: String::from("192.0.2.10"),
+        Datacenter: String::from("dc1"),
+        Service: Some(AgentService {
+            ID: String::from("catalog-test-service-1"),
+            Service: String::from("catalog-test-service"),
+            Port: 8080,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    client.register(&reg, None).await.unwrap();
+
+    let (nodes, _) = client.nodes(None).await.unwrap();
+    let node = nodes
+        .iter()
+        .find(|n| n.Node == "catalog-test-node")
+        .expect("registered node should be in the catalog");
+    assert_eq!(node.Address, "192.0.2.10");
+
+    let (services, _) = client.services(None).await.unwrap();
+    assert!(services.contains_key("catalog-test-service"));
+
+    let dereg = CatalogDeregistration {
+        Node: String::from("catalog-test-node"),
+        Datacenter: String::from("dc1"),
+        ..Default::default()
+    };
+    client.deregister(&dereg, None).await.unwrap();
+
+    let (nodes, _) = client.nodes(None).await.unwrap();
+    assert!(nodes.iter().all(|n| n.Node != "catalog-test-node"));
+}
+
+#[tokio::test]
+async fn ds_service_test() {
+    use consul::catalog::Catalog;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    let (instances, _) = client.service("consul", None, None).await.unwrap();
+    assert!(!instances.is_empty());
+    assert!(instances.iter().all(|s| s.ServiceName == "consul"));
+
+    let (instances, _) = client
+        .service("consul", Some("no-such-tag"), None)
+        .await
+        .unwrap();
+    assert!(instances.is_empty());
+
+    let (instances, _) = client
+        .service("non-existing-service", None, None)
+        .await
+        .unwrap();
+    assert!(instances.is_empty());
+}
+
+#[tokio::test]
+async fn ds_node_test() {
+    use consul::catalog::Catalog;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    let system_hostname = hostname::get().unwrap().into_string().unwrap();
+    let (node, _) = client.node(&system_hostname, None).await.unwrap();
+    let node = node.expect("the local agent should be in the catalog");
+    assert_eq!(node.Node.unwrap().Node, system_hostname);
+    assert!(node.Services.contains_key("consul"));
+
+    let (node, _) = client.node("non-existing-node", None).await.unwrap();
+    assert!(node.is_none());
+}
+
+#[tokio::test]
+async fn ds_query_meta_test() {
+    use consul::catalog::Catalog;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    let (_, meta) = client.nodes(None).await.unwrap();
+    assert!(meta.last_index.unwrap() > 0, "index must be positive");
+    assert!(meta.known_leader, "a dev agent is its own leader");
+}
+
+#[tokio::test]
+async fn ds_consistency_test() {
+    use consul::catalog::Catalog;
+    use consul::{ConsistencyMode, QueryOptions};
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    for consistency in &[ConsistencyMode::Consistent, ConsistencyMode::Stale] {
+        let options = QueryOptions {
+            consistency: *consistency,
+            ..Default::default()
+        };
+        let (nodes, _) = client.nodes(Some(&options)).await.unwrap();
+        assert!(!nodes.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn register_full_node_test() {
+    use consul::agent::{AgentCheck, AgentService, CheckStatus};
+    use consul::catalog::{Catalog, CatalogDeregistration, CatalogRegistration};
+    use consul::health::Health;
+    use std::collections::HashMap;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let tagged_addresses: HashMap<String, String> = vec![
+        (String::from("lan"), String::from("192.0.2.20")),
+        (String::from("wan"), String::from("198.51.100.20")),
+    ]
+    .into_iter()
+    .collect();
+    let node_meta: HashMap<String, String> = vec![(String::from("rack"), String::from("r1"))]
+        .into_iter()
+        .collect();
+    let service_meta: HashMap<String, String> =
+        vec![(String::from("version"), String::from("1.2.3"))]
+            .into_iter()
+            .collect();
+
+    let reg = CatalogRegistration {
+        Node: String::from("catalog-full-node"),
+        Address: String::from("192.0.2.20"),
+        Datacenter: String::from("dc1"),
+        TaggedAddresses: Some(tagged_addresses.clone()),
+        NodeMeta: Some(node_meta.clone()),
+        Service: Some(AgentService {
+            ID: String::from("catalog-full-service-1"),
+            Service: String::from("catalog-full-service"),
+            Tags: Some(vec![String::from("primary"), String::from("v1")]),
+            Meta: Some(service_meta.clone()),
+            Port: 8080,
+            ..Default::default()
+        }),
+        Check: Some(AgentCheck {
+            Node: String::from("catalog-full-node"),
+            CheckID: String::from("catalog-full-check"),
+            Name: String::from("catalog full check"),
+            Status: CheckStatus::Passing,
+            ServiceID: String::from("catalog-full-service-1"),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    client.register(&reg, None).await.unwrap();
+
+    let (node, _) = Catalog::node(&client, "catalog-full-node", None)
+        .await
+        .unwrap();
+    let node = node.expect("registered node should be in the catalog");
+    let info = node.Node.unwrap();
+    assert_eq!(info.TaggedAddresses, Some(tagged_addresses));
+    assert_eq!(info.Meta, Some(node_meta));
+    let service = node.Services.get("catalog-full-service-1").unwrap();
+    assert_eq!(
+        service.Tags,
+        Some(vec![String::from("primary"), String::from("v1")])
+    );
+    assert_eq!(service.Meta, Some(service_meta));
+
+    let (instances, _) = Catalog::service(&client, "catalog-full-service", None, None)
+        .await
+        .unwrap();
+    assert_eq!(instances.len(), 1);
+    assert_eq!(
+        instances[0].ServiceTags,
+        Some(vec![String::from("primary"), String::from("v1")])
+    );
+
+    let (checks, _) = Health::node(&client, "catalog-full-node", None)
+        .await
+        .unwrap();
+    assert!(checks
+        .iter()
+        .any(|c| c.CheckID == "catalog-full-check" && c.Status == CheckStatus::Passing));
+
+    let dereg = CatalogDeregistration {
+        Node: String::from("catalog-full-node"),
+        Datacenter: String::from("dc1"),
+        ..Default::default()
+    };
+    client.deregister(&dereg, None).await.unwrap();
+}
+
+#[tokio::test]
+async fn ds_node_services_test() {
+    use consul::catalog::Catalog;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    let system_hostname = hostname::get().unwrap().into_string().unwrap();
+    let (node, _) = client.node_services(&system_hostname, None).await.unwrap();
+    let node = node.expect("the local agent should be in the catalog");
+    assert_eq!(node.Node.unwrap().Node, system_hostname);
+    assert!(node.Services.iter().any(|s| s.Service == "consul"));
+
+    let (node, _) = client
+        .node_services("non-existing-node", None)
+        .await
+        .unwrap();
+    assert!(node.is_none());
+}
+
+#[tokio::test]
+async fn ds_gateway_services_test() {
+    use consul::catalog::Catalog;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    let (services, _) = client
+        .gateway_services("non-existing-gateway", None)
+        .await
+        .unwrap();
+    assert!(services.is_empty());
+}
+
+#[test]
+fn catalog_name_types_test() {
+    use consul::{Datacenter, NodeName, ServiceName};
+    let node = NodeName::from("node-1");
+    assert_eq!(node.to_string(), "node-1");
+    assert_eq!(ServiceName::from(String::from("web")).as_str(), "web");
+    let dc: Datacenter = serde_json::from_str("\"dc1\"").unwrap();
+    assert_eq!(dc, Datacenter::from("dc1"));
+    assert_eq!(serde_json::to_string(&dc).unwrap(), "\"dc1\"");
+}
+
+#[tokio::test]
+async fn ds_connect_test() {
+    use consul::agent::{Agent, AgentServiceConnect, AgentServiceRegistration};
+    use consul::catalog::Catalog;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    // The agent's own `consul` service is not on the mesh
+    let (services, _) = client.connect("consul", None).await.unwrap();
+    assert!(services.is_empty());
+
+    let reg = AgentServiceRegistration {
+        ID: Some("ds-connect-native".to_string()),
+        Name: "ds-connect-native".to_string(),
+        Port: Some(8443),
+        Connect: Some(AgentServiceConnect { Native: true }),
+        ..Default::default()
+    };
+    client.register_service(&reg).await.unwrap();
+    let (services, _) = client.connect("ds-connect-native", None).await.unwrap();
+    client
+        .deregister_service("ds-connect-native")
+        .await
+        .unwrap();
+    assert_eq!(services.len(), 1);
+    assert_eq!(services[0].ServiceID, "ds-connect-native");
+    assert_eq!(services[0].ServiceName, "ds-connect-native");
+}
+
+#[tokio::test]
+async fn deregister_service_and_check_test() {
+    use consul::agent::{AgentCheck, AgentService};
+    use consul::catalog::{Catalog, CatalogRegistration};
+    use consul::health::Health;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let reg = CatalogRegistration {
+        Node: String::from("dereg-test-node"),
+        Address: String::from("192.0.2.11"),
+        Service: Some(AgentService {
+            ID: String::from("dereg-test-service-1"),
+            Service: String::from("dereg-test-service"),
+            ..Default::default()
+        }),
+        Check: Some(AgentCheck {
+            CheckID: String::from("dereg-test-check"),
+            Name: String::from("dereg test check"),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    client.register(&reg, None).await.unwrap();
+
+    client
+        .catalog_deregister_check("dereg-test-node", "dereg-test-check", None)
+        .await
+        .unwrap();
+    let (checks, _) = Health::node(&client, "dereg-test-node", None)
+        .await
+        .unwrap();
+    assert!(checks.iter().all(|c| c.CheckID != "dereg-test-check"));
+
+    client
+        .catalog_deregister_service("dereg-test-node", "dereg-test-service-1", None)
+        .await
+        .unwrap();
+    let (node, _) = Catalog::node(&client, "dereg-test-node", None)
+        .await
+        .unwrap();
+    assert!(node.unwrap().Services.is_empty());
+
+    let dereg = consul::catalog::CatalogDeregistration {
+        Node: String::from("dereg-test-node"),
+        ..Default::default()
+    };
+    client.deregister(&dereg, None).await.unwrap();
+}
+
+#[tokio::test]
+async fn service_nearest_test() {
+    use consul::catalog::Catalog;
+
+    let instances = ok("[{\"Node\":{\"Node\":\"near\"},\"Service\":{\"ID\":\"web\"}},{\"Node\":{\"Node\":\"far\"},\"Service\":{\"ID\":\"web\"}}]");
+    let no_instances = ok("[]");
+    let (address, requests) = serve(vec![instances, no_instances]).await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    let (nearest, _) = client.service_nearest("web", None).await.unwrap();
+    assert_eq!(nearest.unwrap().Node.Node, "near");
+    let (nearest, _) = client.service_nearest("web", None).await.unwrap();
+    assert!(nearest.is_none());
+    assert_eq!(
+        requests.lines()[0],
+        "get /v1/health/service/web?passing=1&near=_agent http/1.1"
+    );
+}
+
+#[tokio::test]
+async fn nodes_near_test() {
+    use consul::catalog::Catalog;
+    use consul::QueryOptions;
+
+    // A JSON list of `entry` filled with each of `names`
+    let list = |entry: &str, names: &[&str]| {
+        let entries: Vec<String> = names.iter().map(|name| entry.replace("{}", name)).collect();
+        ok(&format!("[{}]", entries.join(",")))
+    };
+    let nodes = |names| list(r#"{"Node":"{}"}"#, names);
+    let instances = |names| list(r#"{"Node":{"Node":"{}"},"Service":{"ID":"web"}}"#, names);
+    // Consul sorts by name, or by round trip time from the `near` node
+    let (address, requests) = serve(vec![
+        nodes(&["node-1", "node-2", "node-3"]),
+        nodes(&["node-2", "node-3", "node-1"]),
+        instances(&["node-3", "node-1"]),
+    ])
+    .await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    let names = |nodes: Vec<consul::catalog::Node>| -> Vec<String> {
+        nodes.into_iter().map(|node| node.Node).collect()
+    };
+    let (by_name, _) = client.nodes(None).await.unwrap();
+    assert_eq!(names(by_name), ["node-1", "node-2", "node-3"]);
+    let options = QueryOptions {
+        near: Some(String::from("node-2")),
+        ..Default::default()
+    };
+    let (by_distance, _) = client.nodes(Some(&options)).await.unwrap();
+    assert_eq!(names(by_distance), ["node-2", "node-3", "node-1"]);
+    let (nearest, _) = client.service_nearest("web", Some(&options)).await.unwrap();
+    assert_eq!(nearest.unwrap().Node.Node, "node-3");
+    assert_eq!(
+        requests.lines(),
+        vec![
+            "get /v1/catalog/nodes? http/1.1",
+            "get /v1/catalog/nodes?near=node-2 http/1.1",
+            "get /v1/health/service/web?passing=1&near=node-2 http/1.1",
+        ]
+    );
+}
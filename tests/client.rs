@@ -0,0 +1,889 @@
+extern crate consul;
+pub mod common;
+
+#[cfg(unix)]
+use common::serve_unix;
+use common::{ok, respond, serve, DATACENTERS, FORBIDDEN, SERVER_ERROR, UNAVAILABLE};
+use consul::{ClientBuilder, RetryPolicy};
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 3,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(10),
+    }
+}
+
+#[tokio::test]
+async fn client_builder_test() {
+    use consul::catalog::Catalog;
+    let client = ClientBuilder::new()
+        .address("http://localhost:8500")
+        .datacenter("dc1")
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap();
+    let r = client.datacenters().await.unwrap();
+    assert_eq!(r.0, ["dc1"]);
+}
+
+#[test]
+fn client_builder_rejects_invalid_address_test() {
+    assert!(ClientBuilder::new()
+        .address("localhost:8500")
+        .build()
+        .is_err());
+    assert!(ClientBuilder::new()
+        .address("ftp://localhost")
+        .build()
+        .is_err());
+    assert!(ClientBuilder::new().address("not a url").build().is_err());
+}
+
+#[tokio::test]
+async fn client_builder_from_env_test() {
+    use consul::catalog::Catalog;
+    use consul::Config;
+    // The only test of this binary to touch the environment, see below
+    std::env::set_var("CONSUL_HTTP_ADDR", "127.0.0.1:8501");
+    std::env::set_var("CONSUL_HTTP_SSL", "true");
+    let tls = tls_fixture();
+    std::env::set_var("CONSUL_CACERT", tls.join("ca.pem"));
+    std::env::set_var("CONSUL_CLIENT_CERT", tls.join("client.pem"));
+    std::env::set_var("CONSUL_CLIENT_KEY", tls.join("client-key.pem"));
+    assert!(ClientBuilder::from_env().build().is_ok());
+    assert!(Config::new_from_env().is_ok());
+    // The certificates are read, and a key that does not match is rejected
+    std::env::set_var("CONSUL_CLIENT_KEY", tls.join("ca.pem"));
+    assert!(ClientBuilder::from_env().build().is_err());
+    assert!(Config::new_from_env().is_err());
+    for var in [
+        "CONSUL_HTTP_SSL",
+        "CONSUL_CACERT",
+        "CONSUL_CLIENT_CERT",
+        "CONSUL_CLIENT_KEY",
+    ] {
+        std::env::remove_var(var);
+    }
+
+    std::env::set_var("CONSUL_HTTP_ADDR", "127.0.0.1:8500");
+    let client = ClientBuilder::from_env().build().unwrap();
+    let r = client.datacenters().await.unwrap();
+    assert_eq!(r.0, ["dc1"]);
+}
+
+#[tokio::test]
+async fn client_timeout_test() {
+    use consul::errors::ErrorKind;
+    use consul::kv::{KVPair, KV};
+    use consul::QueryOptions;
+    let client = ClientBuilder::new()
+        .timeout(Duration::from_millis(500))
+        .build()
+        .unwrap();
+    let pair = KVPair {
+        Key: String::from("timeouttest"),
+        Value: Some(b"value".to_vec()),
+        ..Default::default()
+    };
+    client.put(&pair, None).await.unwrap();
+    let (_, meta) = client.get("timeouttest", None).await.unwrap();
+    // Block on a key nobody writes, well past the client timeout
+    let options = QueryOptions {
+        wait_index: meta.last_index,
+        wait_time: Some(Duration::from_secs(10)),
+        ..Default::default()
+    };
+    match client.get("timeouttest", Some(&options)).await {
+        Err(e) => match e.kind() {
+            ErrorKind::Timeout => {}
+            other => panic!("unexpected error: {}", other),
+        },
+        Ok(_) => panic!("the blocking query should have timed out"),
+    }
+    client.delete("timeouttest", None).await.unwrap();
+}
+
+#[tokio::test]
+async fn client_retry_test() {
+    use consul::catalog::Catalog;
+    let (address, requests) = serve(vec![UNAVAILABLE, SERVER_ERROR, DATACENTERS]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .retry(retry_policy())
+        .build()
+        .unwrap();
+    let r = client.datacenters().await.unwrap();
+    assert_eq!(r.0, ["dc1"]);
+    assert_eq!(requests.count(), 3);
+}
+
+#[tokio::test]
+async fn client_retry_gives_up_test() {
+    use consul::catalog::Catalog;
+    let (address, requests) = serve(vec![SERVER_ERROR; 3]).await;
+    let policy = RetryPolicy {
+        max_retries: 2,
+        ..retry_policy()
+    };
+    let client = ClientBuilder::new()
+        .address(&address)
+        .retry(policy)
+        .build()
+        .unwrap();
+    assert!(client.datacenters().await.is_err());
+    assert_eq!(requests.count(), 3);
+}
+
+#[tokio::test]
+async fn client_no_retry_on_client_error_test() {
+    use consul::catalog::Catalog;
+    let (address, requests) = serve(vec![FORBIDDEN, DATACENTERS]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .retry(retry_policy())
+        .build()
+        .unwrap();
+    assert!(client.datacenters().await.is_err());
+    assert_eq!(requests.count(), 1);
+}
+
+#[tokio::test]
+async fn client_retry_write_opt_in_test() {
+    use consul::kv::{KVPair, KV};
+    use consul::WriteOptions;
+    let stored = ok("true");
+    let (address, requests) = serve(vec![SERVER_ERROR.into(), SERVER_ERROR.into(), stored]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .retry(retry_policy())
+        .build()
+        .unwrap();
+    let pair = KVPair {
+        Key: String::from("retrytest"),
+        Value: Some(b"value".to_vec()),
+        ..Default::default()
+    };
+    // Writes are not retried unless asked to...
+    assert!(client.put(&pair, None).await.is_err());
+    assert_eq!(requests.count(), 1);
+
+    // ...in which case the body is sent again
+    let options = WriteOptions {
+        retry: true,
+        ..Default::default()
+    };
+    assert!(client.put(&pair, Some(&options)).await.unwrap().0);
+    assert_eq!(requests.count(), 3);
+}
+
+#[tokio::test]
+async fn client_error_status_test() {
+    use consul::agent::Agent;
+    use consul::catalog::Catalog;
+    use consul::errors::ErrorKind;
+    let (address, _) = serve(vec![FORBIDDEN, FORBIDDEN]).await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    let read = client.datacenters().await.map(|_| ());
+    let write = client.deregister_check("error-status-test").await;
+    for result in [read, write] {
+        match result {
+            Err(e) => match e.kind() {
+                ErrorKind::Consul(status, body) => {
+                    assert_eq!(*status, 403);
+                    assert_eq!(body, "Permission denied");
+                }
+                other => panic!("unexpected error: {}", other),
+            },
+            Ok(_) => panic!("a 403 answer must be an error"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn client_monitor_lines_test() {
+    use consul::agent::Agent;
+    use futures::StreamExt;
+    let logs = ok("[INFO] first\r\n[DEBUG] second\n");
+    let (address, requests) = serve(vec![logs]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .namespace("team-a")
+        .gzip(true)
+        .build()
+        .unwrap();
+    let logs = client.monitor("debug").await.unwrap();
+    let lines: Vec<String> = logs.map(|l| l.unwrap()).collect().await;
+    assert_eq!(lines, ["[INFO] first", "[DEBUG] second"]);
+    // Compression would hold the lines back until a block fills up
+    assert!(!requests.heads()[0].contains("accept-encoding"));
+    assert_eq!(
+        requests.lines(),
+        ["get /v1/agent/monitor?loglevel=debug&ns=team-a http/1.1"]
+    );
+}
+
+#[tokio::test]
+async fn client_watch_index_reset_test() {
+    use consul::watch::Watch;
+    use futures::StreamExt;
+    const FIRST: &str = "HTTP/1.1 200 OK\r\nX-Consul-Index: 10\r\nContent-Length: 34\r\nConnection: close\r\n\r\n[{\"Key\":\"watched\",\"Value\":\"YQ==\"}]";
+    // The index went backwards, e.g. after a snapshot restore
+    const RESET: &str = "HTTP/1.1 200 OK\r\nX-Consul-Index: 5\r\nContent-Length: 34\r\nConnection: close\r\n\r\n[{\"Key\":\"watched\",\"Value\":\"YQ==\"}]";
+    const DELETED: &str = "HTTP/1.1 404 Not Found\r\nX-Consul-Index: 6\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    let (address, requests) = serve(vec![FIRST, RESET, DELETED]).await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    let mut watch = client.watch_kv("watched");
+    let pair = watch.next().await.unwrap().unwrap().unwrap();
+    assert_eq!(pair.Value.unwrap(), b"a");
+    // The unchanged value after the reset is not yielded
+    assert!(watch.next().await.unwrap().unwrap().is_none());
+
+    let lines = requests.lines();
+    assert_eq!(lines.len(), 3);
+    assert!(!lines[0].contains("index="));
+    assert!(lines[1].contains("index=10"));
+    assert!(lines[2].contains("index=0"));
+}
+
+/// A CA and a client certificate it signed, written to a fresh directory as
+/// `ca.pem`, `client.pem` and `client-key.pem`.
+fn tls_fixture() -> std::path::PathBuf {
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, IsCa};
+    let dir = std::env::temp_dir().join(format!("consul-rust-tls-{}", rand::random::<u32>()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut params = CertificateParams::new(Vec::new());
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    let ca = Certificate::from_params(params).unwrap();
+    let client = Certificate::from_params(CertificateParams::new(vec![String::from(
+        "client.dc1.consul",
+    )]))
+    .unwrap();
+    std::fs::write(dir.join("ca.pem"), ca.serialize_pem().unwrap()).unwrap();
+    std::fs::write(
+        dir.join("client.pem"),
+        client.serialize_pem_with_signer(&ca).unwrap(),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("client-key.pem"),
+        client.serialize_private_key_pem(),
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn client_builder_tls_test() {
+    use consul::tls::TlsConfig;
+    // https without any TLS configuration is rejected upfront
+    let err = ClientBuilder::new()
+        .address("https://localhost:8501")
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("ClientBuilder::tls"));
+
+    assert!(ClientBuilder::new()
+        .address("https://localhost:8501")
+        .tls(TlsConfig::new())
+        .build()
+        .is_ok());
+    let tls = tls_fixture();
+    assert!(ClientBuilder::new()
+        .address("https://localhost:8501")
+        .tls(
+            TlsConfig::new()
+                .ca_cert_path(tls.join("ca.pem"))
+                .client_cert_path(tls.join("client.pem"), tls.join("client-key.pem"))
+                .verify_hostname(false),
+        )
+        .build()
+        .is_ok());
+
+    let ca = std::fs::read(tls.join("ca.pem")).unwrap();
+    assert!(ClientBuilder::new()
+        .address("https://localhost:8501")
+        .tls(TlsConfig::new().ca_cert_pem(&ca))
+        .build()
+        .is_ok());
+    assert!(ClientBuilder::new()
+        .address("https://localhost:8501")
+        .tls(TlsConfig::new().ca_cert_pem(b"not a certificate"))
+        .build()
+        .is_err());
+    assert!(ClientBuilder::new()
+        .address("https://localhost:8501")
+        .tls(TlsConfig::new().ca_cert_path(tls.join("missing.pem")))
+        .build()
+        .is_err());
+    // The key does not match a certificate
+    assert!(ClientBuilder::new()
+        .address("https://localhost:8501")
+        .tls(TlsConfig::new().client_cert_path(tls.join("ca.pem"), tls.join("ca.pem")))
+        .build()
+        .is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn client_unix_socket_test() {
+    use consul::catalog::Catalog;
+    use consul::kv::{KVPair, KV};
+
+    let path = std::env::temp_dir().join(format!("consul-rust-{}.sock", rand::random::<u32>()));
+    let requests = serve_unix(
+        &path,
+        vec![
+            DATACENTERS,
+            "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\ntrue",
+        ],
+    )
+    .await;
+    let client = ClientBuilder::new()
+        .address(&format!("unix://{}", path.display()))
+        .build()
+        .unwrap();
+
+    let (datacenters, _) = client.datacenters().await.unwrap();
+    assert_eq!(datacenters, vec!["dc1".to_owned()]);
+    let pair = KVPair {
+        Key: String::from("unix"),
+        Value: Some(b"socket".to_vec()),
+        ..Default::default()
+    };
+    assert!(client.put(&pair, None).await.unwrap().0);
+    assert_eq!(
+        requests.lines(),
+        vec![
+            "get /v1/catalog/datacenters http/1.1",
+            "put /v1/kv/unix http/1.1"
+        ]
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn client_builder_rejects_empty_unix_socket_test() {
+    assert!(ClientBuilder::new().address("unix://").build().is_err());
+}
+
+#[tokio::test]
+async fn client_namespace_test() {
+    use consul::catalog::Catalog;
+    use consul::errors::ErrorKind;
+    use consul::kv::KV;
+    use consul::{QueryOptions, WriteOptions};
+
+    let bad_namespace = respond("400 Bad Request", "Namespaces is a Consul Enterprise");
+    let (address, requests) = serve(vec![
+        DATACENTERS.into(),
+        bad_namespace.clone(),
+        bad_namespace,
+    ])
+    .await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .namespace("team-a")
+        .build()
+        .unwrap();
+
+    client.datacenters().await.unwrap();
+    let options = QueryOptions {
+        namespace: Some(String::from("team-b")),
+        ..Default::default()
+    };
+    match client.list("app", Some(&options)).await {
+        Err(e) => match e.kind() {
+            ErrorKind::Consul(status, _) => assert_eq!(*status, 400),
+            kind => panic!("unexpected error {:?}", kind),
+        },
+        Ok(_) => panic!("expected an error"),
+    }
+    let options = WriteOptions {
+        namespace: Some(String::from("team-c")),
+        ..Default::default()
+    };
+    assert!(client.delete("app", Some(&options)).await.is_err());
+    assert_eq!(
+        requests.lines(),
+        vec![
+            "get /v1/catalog/datacenters?ns=team-a http/1.1",
+            "get /v1/kv/app?recurse=&ns=team-b http/1.1",
+            "delete /v1/kv/app?ns=team-c http/1.1"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn client_partition_test() {
+    use consul::catalog::Catalog;
+    use consul::health::Health;
+    use consul::QueryOptions;
+
+    let no_checks = ok("[]");
+    let (address, requests) = serve(vec![DATACENTERS.into(), no_checks]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .namespace("team-a")
+        .partition("part-a")
+        .build()
+        .unwrap();
+
+    client.datacenters().await.unwrap();
+    let options = QueryOptions {
+        partition: Some(String::from("part-b")),
+        ..Default::default()
+    };
+    Health::node(&client, "node1", Some(&options))
+        .await
+        .unwrap();
+    assert_eq!(
+        requests.lines(),
+        vec![
+            "get /v1/catalog/datacenters?ns=team-a&partition=part-a http/1.1",
+            "get /v1/health/node/node1?ns=team-a&partition=part-b http/1.1"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn client_filter_test() {
+    use consul::health::Health;
+    use consul::QueryOptions;
+
+    let no_services = ok("[]");
+    let (address, requests) = serve(vec![no_services]).await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    let options = QueryOptions {
+        filter: Some(String::from("ServiceMeta.version == \"v2\"")),
+        ..Default::default()
+    };
+    Health::service(&client, "web", None, true, Some(&options))
+        .await
+        .unwrap();
+    // The mock lowercases what it records, Consul gets `ServiceMeta.version`
+    assert_eq!(
+        requests.lines(),
+        vec!["get /v1/health/service/web?passing=1&filter=servicemeta.version+%3d%3d+%22v2%22 http/1.1"]
+    );
+}
+
+#[tokio::test]
+async fn client_get_raw_test() {
+    const VERSION: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nX-Consul-Index: 7\r\nContent-Length: 20\r\nConnection: close\r\n\r\n{\"Version\":\"1.19.0\"}";
+    let (address, requests) = serve(vec![VERSION]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .datacenter("dc1")
+        .build()
+        .unwrap();
+
+    let (body, headers) = client
+        .get_raw("/v1/agent/version", &[("pretty", "")], None)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"{\"Version\":\"1.19.0\"}");
+    assert_eq!(headers["content-type"], "application/json");
+    assert_eq!(headers["x-consul-index"], "7");
+    assert_eq!(
+        requests.lines(),
+        vec!["get /v1/agent/version?pretty=&dc=dc1 http/1.1"]
+    );
+}
+
+#[tokio::test]
+async fn client_builder_with_http_client_test() {
+    use consul::catalog::Catalog;
+    use consul::tls::TlsConfig;
+
+    let (address, requests) = serve(vec![DATACENTERS]).await;
+    let http_client = reqwest::Client::builder()
+        .pool_max_idle_per_host(0)
+        .build()
+        .unwrap();
+    let client = ClientBuilder::new()
+        .address(&address)
+        .with_http_client(http_client.clone())
+        .build()
+        .unwrap();
+    client.datacenters().await.unwrap();
+    assert_eq!(requests.count(), 1);
+
+    // The given client is in charge of TLS
+    assert!(ClientBuilder::new()
+        .address("https://localhost:8501")
+        .with_http_client(http_client.clone())
+        .build()
+        .is_ok());
+    assert!(ClientBuilder::new()
+        .address("https://localhost:8501")
+        .with_http_client(http_client)
+        .tls(TlsConfig::new())
+        .build()
+        .is_err());
+}
+
+#[tokio::test]
+async fn client_builder_connection_settings_test() {
+    use consul::catalog::Catalog;
+
+    let (address, requests) = serve(vec![DATACENTERS]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .pool_max_idle_per_host(4)
+        .pool_idle_timeout(Duration::from_secs(30))
+        .tcp_keepalive(Duration::from_secs(15))
+        .build()
+        .unwrap();
+    client.datacenters().await.unwrap();
+    assert_eq!(requests.count(), 1);
+
+    assert!(ClientBuilder::new()
+        .address(&address)
+        .tcp_keepalive(Duration::from_secs(15))
+        .with_http_client(reqwest::Client::new())
+        .build()
+        .is_err());
+}
+
+#[tokio::test]
+async fn client_gzip_test() {
+    use consul::catalog::Catalog;
+    use consul::snapshot::Snapshot;
+
+    // `["dc1"]`, gzipped
+    const GZIPPED: [u8; 27] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x8b, 0x56, 0x4a, 0x49, 0x36,
+        0x54, 0x8a, 0x05, 0x00, 0xa0, 0xab, 0x3a, 0x24, 0x07, 0x00, 0x00, 0x00,
+    ];
+    let response = |encoding: &str| {
+        let mut response = format!(
+            "HTTP/1.1 200 OK\r\n{}Content-Length: 27\r\nConnection: close\r\n\r\n",
+            encoding
+        )
+        .into_bytes();
+        response.extend_from_slice(&GZIPPED);
+        response
+    };
+    let (address, requests) =
+        serve(vec![response("Content-Encoding: gzip\r\n"), response("")]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .gzip(true)
+        .build()
+        .unwrap();
+
+    let (datacenters, _) = client.datacenters().await.unwrap();
+    assert_eq!(datacenters, vec!["dc1".to_owned()]);
+    // A snapshot is gzip data itself, only a gzip Content-Encoding is undone
+    let (snapshot, _) = client.save(None).await.unwrap();
+    assert_eq!(snapshot, GZIPPED.to_vec());
+    assert!(requests
+        .heads()
+        .iter()
+        .all(|head| head.contains("accept-encoding: gzip")));
+}
+
+#[tokio::test]
+async fn client_gzip_multiple_members_test() {
+    use consul::catalog::Catalog;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    // Gzip allows a stream of several members, decoded back to back
+    let mut body = Vec::new();
+    for part in ["[\"dc1\",", "\"dc2\"]"] {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(part.as_bytes()).unwrap();
+        body.extend(encoder.finish().unwrap());
+    }
+    let mut response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    response.extend(body);
+    let (address, _) = serve(vec![response]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .gzip(true)
+        .build()
+        .unwrap();
+
+    let (datacenters, _) = client.datacenters().await.unwrap();
+    assert_eq!(datacenters, ["dc1", "dc2"]);
+}
+
+#[tokio::test]
+async fn client_request_token_test() {
+    use consul::kv::{KVPair, KV};
+    use consul::{QueryOptions, WriteOptions};
+
+    let stored = ok("true");
+    let (address, requests) = serve(vec![DATACENTERS.into(), DATACENTERS.into(), stored]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .token("client-token")
+        .build()
+        .unwrap();
+
+    consul::catalog::Catalog::datacenters(&client)
+        .await
+        .unwrap();
+    let options = QueryOptions {
+        token: Some(String::from("read-token")),
+        ..Default::default()
+    };
+    client
+        .get_raw("/v1/catalog/datacenters", &[], Some(&options))
+        .await
+        .unwrap();
+    let options = WriteOptions {
+        token: Some(String::from("write-token")),
+        ..Default::default()
+    };
+    let pair = KVPair {
+        Key: String::from("impersonated"),
+        ..Default::default()
+    };
+    client.put(&pair, Some(&options)).await.unwrap();
+
+    let heads = requests.heads();
+    assert!(heads[0].contains("x-consul-token: client-token\r\n"));
+    for (head, token) in heads[1..].iter().zip(["read-token", "write-token"]) {
+        assert!(head.contains(&format!("x-consul-token: {}\r\n", token)));
+        assert!(!head.contains("client-token"));
+    }
+}
+
+#[test]
+fn client_debug_redacts_tokens_test() {
+    use consul::{QueryOptions, WriteOptions};
+
+    let builder = ClientBuilder::new().token("top-secret-token");
+    assert!(!format!("{:?}", builder).contains("top-secret-token"));
+    let client = builder.build().unwrap();
+    let debug = format!("{:?}", client);
+    assert!(!debug.contains("top-secret-token"));
+    assert!(debug.contains("token: Some(***)"));
+
+    let query = QueryOptions {
+        token: Some(String::from("top-secret-token")),
+        ..Default::default()
+    };
+    assert!(!format!("{:?}", query).contains("top-secret-token"));
+    let write = WriteOptions {
+        token: Some(String::from("top-secret-token")),
+        ..Default::default()
+    };
+    assert!(!format!("{:?}", write).contains("top-secret-token"));
+}
+
+#[tokio::test]
+async fn client_errors_omit_token_test() {
+    let (address, _) = serve(vec![FORBIDDEN]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .token("top-secret-token")
+        .build()
+        .unwrap();
+    let e = consul::catalog::Catalog::datacenters(&client)
+        .await
+        .unwrap_err();
+    assert!(!format!("{} {:?}", e, e).contains("top-secret-token"));
+}
+
+/// Collects the fields recorded on `consul.request` spans, as `name=value`.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Default)]
+struct SpanFields(Arc<Mutex<Vec<String>>>);
+
+#[cfg(feature = "tracing")]
+impl SpanFields {
+    fn push(&self, values: &dyn Fn(&mut dyn tracing::field::Visit)) {
+        struct Collect<'a>(&'a mut Vec<String>);
+        impl tracing::field::Visit for Collect<'_> {
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                self.0.push(format!("{}={:?}", field.name(), value));
+            }
+        }
+        values(&mut Collect(&mut self.0.lock().unwrap()));
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for SpanFields {
+    fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+        metadata.name() == "consul.request"
+    }
+
+    fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        self.push(&|visit| span.record(visit));
+        tracing::span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+        self.push(&|visit| values.record(visit));
+    }
+
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+    fn event(&self, _: &tracing::Event<'_>) {}
+    fn enter(&self, _: &tracing::span::Id) {}
+    fn exit(&self, _: &tracing::span::Id) {}
+}
+
+#[cfg(feature = "tracing")]
+#[tokio::test]
+async fn client_tracing_span_test() {
+    use consul::catalog::Catalog;
+
+    const INDEXED: &str = "HTTP/1.1 200 OK\r\nX-Consul-Index: 42\r\nContent-Type: application/json\r\nContent-Length: 7\r\nConnection: close\r\n\r\n[\"dc1\"]";
+    let fields = SpanFields::default();
+    let _guard = tracing::subscriber::set_default(fields.clone());
+    let (address, _) = serve(vec![INDEXED]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .datacenter("dc1")
+        .token("top-secret-token")
+        .build()
+        .unwrap();
+    client.datacenters().await.unwrap();
+
+    let fields = fields.0.lock().unwrap().clone();
+    assert_eq!(
+        fields,
+        [
+            "method=\"GET\"",
+            "path=\"/v1/catalog/datacenters\"",
+            "status=200",
+            "index=42",
+        ]
+    );
+}
+
+#[cfg(feature = "metrics")]
+#[tokio::test]
+async fn client_request_observer_test() {
+    use consul::kv::KV;
+    use consul::metrics::{RequestInfo, RequestObserver, StatusClass};
+
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl RequestObserver for Recorder {
+        fn before(&self, request: &RequestInfo<'_>) {
+            let event = format!("before {} {}", request.method, request.endpoint);
+            self.0.lock().unwrap().push(event);
+        }
+
+        fn after(&self, request: &RequestInfo<'_>, status: StatusClass, _: Duration) {
+            let event = format!("after {} {}", request.endpoint, status.as_str());
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    let (address, _) = serve(vec![DATACENTERS, FORBIDDEN]).await;
+    let recorder = Recorder::default();
+    let client = ClientBuilder::new()
+        .address(&address)
+        .observer(recorder.clone())
+        .build()
+        .unwrap();
+    consul::catalog::Catalog::datacenters(&client)
+        .await
+        .unwrap();
+    client.get("forbidden", None).await.unwrap_err();
+    // Nothing listens anymore
+    client.get("gone", None).await.unwrap_err();
+
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        [
+            "before GET catalog",
+            "after catalog 2xx",
+            "before GET kv",
+            "after kv 4xx",
+            "before GET kv",
+            "after kv failed",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn client_no_cluster_leader_test() {
+    use consul::catalog::Catalog;
+    use consul::errors::ErrorKind;
+
+    let no_leader = respond("500 Internal Server Error", "rpc error: No cluster leader");
+    let (address, requests) = serve(vec![
+        no_leader.clone(),
+        no_leader.clone(),
+        DATACENTERS.into(),
+        no_leader,
+    ])
+    .await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .retry(retry_policy())
+        .build()
+        .unwrap();
+    assert_eq!(client.datacenters().await.unwrap().0, ["dc1"]);
+    assert_eq!(requests.count(), 3);
+
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+    let e = client.datacenters().await.unwrap_err();
+    assert!(matches!(e.kind(), ErrorKind::NoClusterLeader));
+    let cause = e.iter().nth(1).unwrap().to_string();
+    assert_eq!(
+        cause,
+        "consul returned HTTP 500: rpc error: No cluster leader"
+    );
+}
+
+#[test]
+fn client_traits_object_safe_test() {
+    use consul::acl::ACL;
+    use consul::agent::Agent;
+    use consul::catalog::Catalog;
+    use consul::config_entry::ConfigEntry;
+    use consul::connect_ca::ConnectCA;
+    use consul::connect_intentions::ConnectIntentions;
+    use consul::coordinate::Coordinate;
+    use consul::event::Event;
+    use consul::health::Health;
+    use consul::kv::KV;
+    use consul::operator::Operator;
+    use consul::query::Query;
+    use consul::session::Session;
+    use consul::snapshot::Snapshot;
+    use consul::status::Status;
+    use consul::txn::Txn;
+    use consul::watch::Watch;
+
+    // Callers can hold the client behind any of its traits, e.g. to mock it
+    let client = ClientBuilder::new().build().unwrap();
+    let _: &dyn ACL = &client;
+    let _: &dyn Agent = &client;
+    let _: &dyn Catalog = &client;
+    let _: &dyn ConfigEntry = &client;
+    let _: &dyn ConnectCA = &client;
+    let _: &dyn ConnectIntentions = &client;
+    let _: &dyn Coordinate = &client;
+    let _: &dyn Event = &client;
+    let _: &dyn Health = &client;
+    let _: &dyn KV = &client;
+    let _: &dyn Operator = &client;
+    let _: &dyn Query = &client;
+    let _: &dyn Session = &client;
+    let _: &dyn Snapshot = &client;
+    let _: &dyn Status = &client;
+    let _: &dyn Txn = &client;
+    let _: &dyn Watch = &client;
+}
@@ -0,0 +1,122 @@
+//! A mock Consul agent for the tests that do not need a real one, and the
+//! client of the real one for those that do.
+//!
+//! The test crates declare the module `pub`, as each only uses some of the
+//! helpers.
+
+use consul::{Client, ClientBuilder};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Answers each connection with the next of `responses`, returning the
+/// server's address and the requests it received.
+pub async fn serve<R>(responses: Vec<R>) -> (String, Requests)
+where
+    R: AsRef<[u8]> + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Requests::default();
+    let received = requests.clone();
+    tokio::spawn(async move {
+        for response in responses {
+            let (socket, _) = listener.accept().await.unwrap();
+            answer(socket, response, &received).await;
+        }
+    });
+    (address, requests)
+}
+
+/// Like `serve`, on a Unix socket at `path`.
+#[cfg(unix)]
+pub async fn serve_unix(path: &std::path::Path, responses: Vec<&'static str>) -> Requests {
+    let listener = tokio::net::UnixListener::bind(path).unwrap();
+    let requests = Requests::default();
+    let received = requests.clone();
+    tokio::spawn(async move {
+        for response in responses {
+            let (socket, _) = listener.accept().await.unwrap();
+            answer(socket, response, &received).await;
+        }
+    });
+    requests
+}
+
+async fn answer<S>(mut socket: S, response: impl AsRef<[u8]>, received: &Requests)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    // Read the headers, then as much body as they announce
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = socket.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+    }
+    let text = String::from_utf8_lossy(&request).to_lowercase();
+    let head_len = text.find("\r\n\r\n").unwrap() + 4;
+    let body_len = text
+        .lines()
+        .find_map(|l| l.strip_prefix("content-length:"))
+        .map_or(0, |l| l.trim().parse().unwrap());
+    while request.len() < head_len + body_len {
+        let n = socket.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+    }
+    received.0.lock().unwrap().push(text[..head_len].to_owned());
+    socket.write_all(response.as_ref()).await.unwrap();
+}
+
+#[derive(Clone, Default)]
+pub struct Requests(Arc<Mutex<Vec<String>>>);
+
+impl Requests {
+    pub fn count(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    /// The request lines, lowercased.
+    pub fn lines(&self) -> Vec<String> {
+        let heads = self.0.lock().unwrap();
+        heads
+            .iter()
+            .map(|head| head.lines().next().unwrap_or_default().to_owned())
+            .collect()
+    }
+
+    /// The request lines and headers, lowercased.
+    pub fn heads(&self) -> Vec<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+// Matches the master token configured in data/consul-test.json
+const MASTER_TOKEN: &str = "consul-rust-test-master-token";
+
+/// A client of the test agent, with the master token.
+pub fn set_up() -> Client {
+    ClientBuilder::new().token(MASTER_TOKEN).build().unwrap()
+}
+
+/// A response with `status`, e.g. `404 Not Found`, and `body`.
+pub fn respond(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// A `200 OK` response with `body`.
+pub fn ok(body: &str) -> String {
+    respond("200 OK", body)
+}
+
+pub const UNAVAILABLE: &str = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+pub const SERVER_ERROR: &str =
+    "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+pub const FORBIDDEN: &str =
+    "HTTP/1.1 403 Forbidden\r\nContent-Length: 17\r\nConnection: close\r\n\r\nPermission denied";
+pub const DATACENTERS: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 7\r\nConnection: close\r\n\r\n[\"dc1\"]";
@@ -0,0 +1,85 @@
+extern crate consul;
+pub mod common;
+
+use common::set_up;
+use consul::config_entry::{ConfigEntry, Entry, ProxyDefaultsEntry, ServiceDefaultsEntry};
+
+#[tokio::test]
+async fn config_entry_service_defaults_test() {
+    let client = set_up();
+
+    let entry = Entry::ServiceDefaults(ServiceDefaultsEntry {
+        Name: String::from("config-entry-test"),
+        Protocol: String::from("http"),
+        ..Default::default()
+    });
+    assert!(client.apply(&entry, None).await.unwrap().0);
+
+    let (read, _) = client
+        .get("service-defaults", "config-entry-test", None)
+        .await
+        .unwrap();
+    assert_eq!(read.kind(), "service-defaults");
+    assert_eq!(read.name(), "config-entry-test");
+    match read {
+        Entry::ServiceDefaults(e) => assert_eq!(e.Protocol, "http"),
+        other => panic!("unexpected entry: {:?}", other),
+    }
+
+    let (entries, _) = client.list("service-defaults", None).await.unwrap();
+    assert!(entries.iter().any(|e| e.name() == "config-entry-test"));
+
+    client
+        .delete("service-defaults", "config-entry-test", None)
+        .await
+        .unwrap();
+    let (entries, _) = client.list("service-defaults", None).await.unwrap();
+    assert!(entries.iter().all(|e| e.name() != "config-entry-test"));
+}
+
+#[tokio::test]
+async fn config_entry_proxy_defaults_test() {
+    use serde_json::json;
+    let client = set_up();
+
+    let config = vec![(String::from("protocol"), json!("http"))]
+        .into_iter()
+        .collect();
+    let entry = Entry::ProxyDefaults(ProxyDefaultsEntry {
+        Name: String::from("global"),
+        Config: Some(config),
+        ..Default::default()
+    });
+    assert!(client.apply(&entry, None).await.unwrap().0);
+
+    let (read, _) = client.get("proxy-defaults", "global", None).await.unwrap();
+    match read {
+        Entry::ProxyDefaults(e) => {
+            assert_eq!(e.Config.unwrap().get("protocol"), Some(&json!("http")))
+        }
+        other => panic!("unexpected entry: {:?}", other),
+    }
+
+    client
+        .delete("proxy-defaults", "global", None)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn config_entry_kind_tag_test() {
+    use consul::config_entry::{ServiceSplit, ServiceSplitterEntry};
+    let entry = Entry::ServiceSplitter(ServiceSplitterEntry {
+        Name: String::from("web"),
+        Splits: vec![ServiceSplit {
+            Weight: 100.0,
+            ServiceSubset: String::from("v2"),
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+    let encoded = serde_json::to_value(&entry).unwrap();
+    assert_eq!(encoded["Kind"], "service-splitter");
+    assert_eq!(encoded["Name"], "web");
+    assert_eq!(serde_json::from_value::<Entry>(encoded).unwrap(), entry);
+}
@@ -0,0 +1,76 @@
+extern crate consul;
+pub mod common;
+
+use common::set_up;
+use consul::connect_ca::ConnectCA;
+
+#[tokio::test]
+async fn ca_roots_test() {
+    let client = set_up();
+    let (roots, _) = client.ca_roots(None).await.unwrap();
+    assert!(roots.TrustDomain.ends_with(".consul"));
+    let active = roots
+        .Roots
+        .iter()
+        .find(|r| r.ID == roots.ActiveRootID)
+        .expect("the active root should be listed");
+    assert!(active.Active);
+    assert!(active.RootCert.starts_with("-----BEGIN CERTIFICATE-----"));
+}
+
+#[tokio::test]
+async fn ca_config_test() {
+    let client = set_up();
+    let (config, _) = client.ca_get_config(None).await.unwrap();
+    assert_eq!(config.Provider, "consul");
+
+    // Writing the current configuration back keeps the same root
+    let (before, _) = client.ca_roots(None).await.unwrap();
+    client.ca_set_config(&config, None).await.unwrap();
+    let (after, _) = client.ca_roots(None).await.unwrap();
+    assert_eq!(before.ActiveRootID, after.ActiveRootID);
+}
+
+#[tokio::test]
+async fn agent_connect_leaf_test() {
+    use consul::agent::Agent;
+    use consul::QueryOptions;
+    use std::time::{Duration, SystemTime};
+    let client = set_up();
+
+    let (leaf, meta) = client.connect_leaf("leaf-test", None).await.unwrap();
+    assert_eq!(leaf.Service, "leaf-test");
+    assert!(leaf.ServiceURI.ends_with("/svc/leaf-test"));
+    assert!(leaf.CertPEM.starts_with("-----BEGIN CERTIFICATE-----"));
+    assert!(!leaf.PrivateKeyPEM.is_empty());
+    assert!(leaf.valid_before().unwrap() > SystemTime::now());
+
+    // Blocks until renewal, the same certificate comes back on timeout
+    let options = QueryOptions {
+        wait_index: meta.last_index,
+        wait_time: Some(Duration::from_secs(1)),
+        ..Default::default()
+    };
+    let (same, _) = client
+        .connect_leaf("leaf-test", Some(&options))
+        .await
+        .unwrap();
+    assert_eq!(same.SerialNumber, leaf.SerialNumber);
+}
+
+#[tokio::test]
+async fn agent_connect_authorize_test() {
+    use consul::agent::{Agent, AuthorizeRequest};
+    let client = set_up();
+
+    let (leaf, _) = client.connect_leaf("authorize-client", None).await.unwrap();
+    let req = AuthorizeRequest {
+        Target: String::from("authorize-target"),
+        ClientCertURI: leaf.ServiceURI,
+        ClientCertSerial: leaf.SerialNumber,
+    };
+    // The test agent allows by default, and no intention says otherwise
+    let authorized = client.connect_authorize(&req).await.unwrap();
+    assert!(authorized.Authorized, "denied: {}", authorized.Reason);
+    assert!(!authorized.Reason.is_empty());
+}
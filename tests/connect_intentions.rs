@@ -0,0 +1,50 @@
+extern crate consul;
+pub mod common;
+
+use common::set_up;
+use consul::connect_intentions::{ConnectIntentions, Intention, IntentionAction};
+
+#[tokio::test]
+async fn intention_test() {
+    let client = set_up();
+
+    let intention = Intention {
+        SourceName: String::from("intention-test-web"),
+        DestinationName: String::from("intention-test-db"),
+        Action: IntentionAction::Deny,
+        ..Default::default()
+    };
+    let (id, _) = client.intention_create(&intention, None).await.unwrap();
+    assert!(!id.is_empty());
+
+    let (read, _) = client.intention_get(&id, None).await.unwrap();
+    assert_eq!(read.SourceName, "intention-test-web");
+    assert_eq!(read.Action, IntentionAction::Deny);
+    assert!(read.Precedence > 0);
+
+    let (intentions, _) = client.intention_list(None).await.unwrap();
+    assert!(intentions.iter().any(|i| i.ID == id));
+
+    let (matches, _) = client
+        .intention_match("destination", "intention-test-db", None)
+        .await
+        .unwrap();
+    assert!(matches.iter().any(|i| i.ID == id));
+    let (matches, _) = client
+        .intention_match("source", "intention-test-other", None)
+        .await
+        .unwrap();
+    assert!(matches.iter().all(|i| i.ID != id));
+
+    assert!(!client
+        .intention_check("intention-test-web", "intention-test-db")
+        .await
+        .unwrap());
+    // Nothing matches, so the allow default policy applies
+    assert!(client
+        .intention_check("intention-test-other", "intention-test-db")
+        .await
+        .unwrap());
+
+    client.intention_delete(&id, None).await.unwrap();
+}
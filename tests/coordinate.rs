@@ -0,0 +1,64 @@
+extern crate consul;
+use consul::coordinate::{Coordinate, NetworkCoordinate};
+use consul::{Client, Config};
+use std::time::Duration;
+
+#[tokio::test]
+async fn coordinate_datacenters_test() {
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    let datacenters = client.datacenters().await.unwrap();
+    assert!(datacenters.iter().any(|dc| dc.Datacenter == "dc1"));
+}
+
+#[tokio::test]
+async fn coordinate_nodes_test() {
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    // Coordinates are only known after a few gossip rounds, just check the
+    // endpoint answers
+    let (nodes, meta) = client.nodes(None).await.unwrap();
+    assert!(meta.last_index.is_some());
+    assert!(nodes.iter().all(|n| !n.Node.is_empty()));
+}
+
+#[test]
+fn coordinate_distance_test() {
+    let a = NetworkCoordinate {
+        Vec: vec![0.0, 0.0],
+        Height: 0.001,
+        ..Default::default()
+    };
+    let b = NetworkCoordinate {
+        Vec: vec![0.003, 0.004],
+        Height: 0.002,
+        ..Default::default()
+    };
+    // 5ms apart plus both heights
+    let rtt = a.distance_to(&b).unwrap();
+    assert!((rtt.as_secs_f64() - 0.008).abs() < 1e-9);
+    assert_eq!(a.distance_to(&b), b.distance_to(&a));
+
+    // Adjustments apply...
+    let adjusted = NetworkCoordinate {
+        Adjustment: -0.002,
+        ..b.clone()
+    };
+    let rtt = a.distance_to(&adjusted).unwrap();
+    assert!((rtt.as_secs_f64() - 0.006).abs() < 1e-9);
+
+    // ...unless they would make the estimate negative
+    let overadjusted = NetworkCoordinate {
+        Adjustment: -1.0,
+        ..b.clone()
+    };
+    let rtt = a.distance_to(&overadjusted).unwrap();
+    assert!((rtt.as_secs_f64() - 0.008).abs() < 1e-9);
+
+    let other_pool = NetworkCoordinate {
+        Vec: vec![0.0; 8],
+        ..Default::default()
+    };
+    assert_eq!(a.distance_to(&other_pool), None);
+    assert_eq!(a.distance_to(&a), Some(Duration::from_secs_f64(0.002)));
+}
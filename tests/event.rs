@@ -0,0 +1,71 @@
+extern crate consul;
+use consul::event::{Event, EventFilter};
+use consul::{Client, Config};
+
+#[tokio::test]
+async fn event_test() {
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let (fired, _) = client
+        .fire("event-test", b"payload", None, None)
+        .await
+        .unwrap();
+    assert!(!fired.ID.is_empty());
+    assert_eq!(fired.Name, "event-test");
+    assert_eq!(fired.Payload, b"payload");
+
+    let filter = EventFilter {
+        service: Some(String::from("event-test-service")),
+        tag: Some(String::from("primary")),
+        ..Default::default()
+    };
+    let (filtered, _) = client
+        .fire("event-test-filtered", b"", Some(&filter), None)
+        .await
+        .unwrap();
+    assert_eq!(filtered.ServiceFilter, "event-test-service");
+    assert_eq!(filtered.TagFilter, "primary");
+    assert!(filtered.Payload.is_empty());
+
+    let (events, _) = client.list(Some("event-test"), None).await.unwrap();
+    let event = events.iter().find(|e| e.ID == fired.ID).unwrap();
+    assert_eq!(event.Payload, b"payload");
+    assert!(events.iter().all(|e| e.Name == "event-test"));
+}
+
+#[tokio::test]
+async fn event_payload_too_large_test() {
+    use consul::errors::ErrorKind;
+    use consul::event::MAX_PAYLOAD_SIZE;
+    use consul::ClientBuilder;
+
+    // Rejected before any request goes out, no agent needed
+    let client = ClientBuilder::new()
+        .address("http://127.0.0.1:1")
+        .build()
+        .unwrap();
+    let payload = vec![0; MAX_PAYLOAD_SIZE + 1];
+    let e = client
+        .fire("too-large", &payload, None, None)
+        .await
+        .unwrap_err();
+    match e.kind() {
+        ErrorKind::PayloadTooLarge(limit, actual) => {
+            assert_eq!(*limit, MAX_PAYLOAD_SIZE);
+            assert_eq!(*actual, MAX_PAYLOAD_SIZE + 1);
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+
+    let client = ClientBuilder::new()
+        .address("http://127.0.0.1:1")
+        .max_event_payload(16)
+        .build()
+        .unwrap();
+    let e = client
+        .fire("too-large", &[0; 17], None, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(e.kind(), ErrorKind::PayloadTooLarge(16, 17)));
+}
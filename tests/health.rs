@@ -1,5 +1,8 @@
 extern crate consul;
-use consul::{Client, Config};
+pub mod common;
+
+use common::{ok, serve};
+use consul::{Client, ClientBuilder, Config};
 
 #[tokio::test]
 async fn health_test() {
@@ -27,3 +30,126 @@ async fn health_test() {
         assert!(meta.last_index.unwrap() > 0, "index must be positive");
     }
 }
+
+#[tokio::test]
+async fn health_service_with_tags_test() {
+    use consul::health::Health;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    let (snodes, _) = client
+        .service_with_tags("consul", &[], true, None)
+        .await
+        .unwrap();
+    assert!(!snodes.is_empty(), "should have at least one Service Node");
+    // The consul service carries no tags, so any tag filter excludes it
+    let (snodes, _) = client
+        .service_with_tags("consul", &["primary", "v1"], true, None)
+        .await
+        .unwrap();
+    assert!(snodes.is_empty());
+}
+
+#[tokio::test]
+async fn health_state_test() {
+    use consul::agent::CheckStatus;
+    use consul::errors::ErrorKind;
+    use consul::health::Health;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    // The agent's own serfHealth check is always registered
+    let (checks, _) = client.state("any", None).await.unwrap();
+    assert!(checks.iter().any(|c| c.CheckID == "serfHealth"));
+
+    let (checks, _) = client.state("passing", None).await.unwrap();
+    assert!(checks.iter().all(|c| c.Status == CheckStatus::Passing));
+
+    match client.state("healthy", None).await {
+        Err(e) => match e.kind() {
+            ErrorKind::InvalidCheckState(state) => assert_eq!(state, "healthy"),
+            other => panic!("unexpected error: {}", other),
+        },
+        Ok(_) => panic!("an invalid state must be rejected"),
+    }
+}
+
+#[tokio::test]
+async fn health_node_test() {
+    use consul::health::Health;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    let system_hostname = hostname::get().unwrap().into_string().unwrap();
+    let (checks, _) = client.node(&system_hostname, None).await.unwrap();
+    assert!(checks.iter().all(|c| c.Node == system_hostname));
+    assert!(checks.iter().any(|c| c.CheckID == "serfHealth"));
+
+    let (checks, _) = client.node("non-existing-node", None).await.unwrap();
+    assert!(checks.is_empty());
+}
+
+#[tokio::test]
+async fn health_checks_test() {
+    use consul::health::Health;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    let (checks, _) = client.checks("non-existing-service", None).await.unwrap();
+    assert!(checks.is_empty());
+}
+
+#[tokio::test]
+async fn health_connect_test() {
+    use consul::agent::{Agent, AgentServiceConnect, AgentServiceRegistration};
+    use consul::health::Health;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+    // The agent's own `consul` service is not on the mesh
+    let (entries, meta) = client.connect("consul", true, None).await.unwrap();
+    assert!(entries.is_empty());
+    assert!(meta.last_index.unwrap() > 0, "index must be positive");
+
+    // Without checks the service is passing
+    let reg = AgentServiceRegistration {
+        ID: Some("health-connect-native".to_string()),
+        Name: "health-connect-native".to_string(),
+        Port: Some(8443),
+        Connect: Some(AgentServiceConnect { Native: true }),
+        ..Default::default()
+    };
+    client.register_service(&reg).await.unwrap();
+    let (entries, _) = client
+        .connect("health-connect-native", true, None)
+        .await
+        .unwrap();
+    client
+        .deregister_service("health-connect-native")
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].Service.ID, "health-connect-native");
+}
+
+#[tokio::test]
+async fn health_service_node_meta_test() {
+    use consul::health::Health;
+    use consul::QueryOptions;
+
+    let no_services = ok("[]");
+    let (address, requests) = serve(vec![no_services]).await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    let mut options = QueryOptions::default();
+    options
+        .node_meta
+        .insert(String::from("zone"), String::from("us-east-1a"));
+    options
+        .node_meta
+        .insert(String::from("rack"), String::from("r1"));
+    client
+        .service("web", None, false, Some(&options))
+        .await
+        .unwrap();
+    // Consul only returns nodes matching every pair
+    assert_eq!(
+        requests.lines(),
+        vec!["get /v1/health/service/web?node-meta=rack%3ar1&node-meta=zone%3aus-east-1a http/1.1"]
+    );
+}
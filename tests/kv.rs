@@ -1,6 +1,10 @@
 extern crate consul;
+pub mod common;
+
+use common::{ok, respond, serve, FORBIDDEN, SERVER_ERROR};
 use consul::kv::KVPair;
-use consul::{Client, Config};
+use consul::session::SessionEntry;
+use consul::{Client, ClientBuilder, Config};
 
 #[tokio::test]
 async fn kv_test() {
@@ -12,15 +16,14 @@ async fn kv_test() {
 
     let pair = KVPair {
         Key: String::from("testkey"),
-        Value: String::from("testvalue"),
+        Value: Some(b"testvalue".to_vec()),
         ..Default::default()
     };
 
     assert!(client.put(&pair, None).await.unwrap().0);
 
-    let b64val = client.get("testkey", None).await.unwrap().0.unwrap().Value;
-    let bytes = base64::decode(b64val).unwrap();
-    assert_eq!(std::str::from_utf8(&bytes).unwrap(), "\"testvalue\"");
+    let value = client.get("testkey", None).await.unwrap().0.unwrap().Value;
+    assert_eq!(value.unwrap(), b"testvalue");
 
     let r = client.list("t", None).await.unwrap();
     assert!(!r.0.is_empty());
@@ -30,3 +33,385 @@ async fn kv_test() {
     let r = client.list("", None).await.unwrap();
     assert!(r.0.is_empty());
 }
+
+#[tokio::test]
+async fn kv_keys_test() {
+    use consul::kv::KV;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    for key in &["keystest/a/1", "keystest/a/2", "keystest/b"] {
+        let pair = KVPair {
+            Key: String::from(*key),
+            Value: Some(b"value".to_vec()),
+            ..Default::default()
+        };
+        assert!(client.put(&pair, None).await.unwrap().0);
+    }
+
+    let (keys, _) = client.keys("keystest/", None, None).await.unwrap();
+    assert_eq!(keys, ["keystest/a/1", "keystest/a/2", "keystest/b"]);
+
+    let (keys, _) = client.keys("keystest/", Some("/"), None).await.unwrap();
+    assert_eq!(keys, ["keystest/a/", "keystest/b"]);
+
+    let (keys, _) = client.keys("no-such-prefix/", None, None).await.unwrap();
+    assert!(keys.is_empty());
+
+    for key in &["keystest/a/1", "keystest/a/2", "keystest/b"] {
+        client.delete(key, None).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn kv_cas_test() {
+    use consul::kv::KV;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let pair = KVPair {
+        Key: String::from("castest"),
+        Value: Some(b"first".to_vec()),
+        ..Default::default()
+    };
+
+    // An index of 0 only succeeds when the key does not exist yet
+    assert!(client.cas(&pair, 0, None).await.unwrap().0);
+    assert!(!client.cas(&pair, 0, None).await.unwrap().0);
+
+    let stored = client.get("castest", None).await.unwrap().0.unwrap();
+    let index = stored.ModifyIndex.unwrap();
+
+    let pair = KVPair {
+        Value: Some(b"second".to_vec()),
+        ..pair
+    };
+    assert!(!client.cas(&pair, index + 1, None).await.unwrap().0);
+    assert!(client.cas(&pair, index, None).await.unwrap().0);
+
+    let stored = client.get("castest", None).await.unwrap().0.unwrap();
+    assert_eq!(stored.Value.unwrap(), b"second");
+
+    client.delete("castest", None).await.unwrap();
+}
+
+#[tokio::test]
+async fn kv_lock_test() {
+    use consul::kv::KV;
+    use consul::session::Session;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let holder = client
+        .create(&SessionEntry::default(), None)
+        .await
+        .unwrap()
+        .0
+        .ID;
+    let contender = client
+        .create(&SessionEntry::default(), None)
+        .await
+        .unwrap()
+        .0
+        .ID;
+
+    let pair = KVPair {
+        Key: String::from("locktest"),
+        Value: Some(b"leader".to_vec()),
+        Session: Some(holder.clone()),
+        ..Default::default()
+    };
+    let contender_pair = KVPair {
+        Session: Some(contender.clone()),
+        ..pair.clone()
+    };
+
+    assert!(client.acquire(&pair, None).await.unwrap().0);
+    // Already held by another session
+    assert!(!client.acquire(&contender_pair, None).await.unwrap().0);
+    // Not the holder
+    assert!(!client.release(&contender_pair, None).await.unwrap().0);
+
+    let stored = client.get("locktest", None).await.unwrap().0.unwrap();
+    assert_eq!(stored.Session, Some(holder.clone()));
+
+    assert!(client.release(&pair, None).await.unwrap().0);
+    assert!(client.acquire(&contender_pair, None).await.unwrap().0);
+
+    client.delete("locktest", None).await.unwrap();
+    client.destroy(&holder, None).await.unwrap();
+    client.destroy(&contender, None).await.unwrap();
+}
+
+#[tokio::test]
+async fn kv_blocking_get_test() {
+    use consul::kv::KV;
+    use consul::QueryOptions;
+    use std::time::{Duration, Instant};
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let pair = KVPair {
+        Key: String::from("blockingtest"),
+        Value: Some(b"before".to_vec()),
+        ..Default::default()
+    };
+    assert!(client.put(&pair, None).await.unwrap().0);
+    let (_, meta) = client.get("blockingtest", None).await.unwrap();
+    let index = meta.last_index.unwrap();
+
+    let writer = client.clone();
+    let update = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let pair = KVPair {
+            Key: String::from("blockingtest"),
+            Value: Some(b"after".to_vec()),
+            ..Default::default()
+        };
+        writer.put(&pair, None).await.unwrap();
+    });
+
+    let options = QueryOptions {
+        wait_index: Some(index),
+        wait_time: Some(Duration::from_secs(10)),
+        ..Default::default()
+    };
+    let start = Instant::now();
+    let (pair, meta) = client.get("blockingtest", Some(&options)).await.unwrap();
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert!(meta.last_index.unwrap() > index);
+    assert_eq!(pair.unwrap().Value.unwrap(), b"after");
+
+    update.await.unwrap();
+    client.delete("blockingtest", None).await.unwrap();
+}
+
+#[tokio::test]
+async fn kv_datacenter_override_test() {
+    use consul::kv::KV;
+    use consul::{QueryOptions, WriteOptions};
+    let mut config = Config::new().unwrap();
+    config.datacenter = Some(String::from("dc-unknown"));
+    let client = Client::new(config);
+
+    let pair = KVPair {
+        Key: String::from("dctest"),
+        Value: Some(b"value".to_vec()),
+        ..Default::default()
+    };
+    // The client-level datacenter does not exist...
+    assert!(client.put(&pair, None).await.is_err());
+
+    // ...but a per-request datacenter takes precedence over it
+    let write_options = WriteOptions {
+        datacenter: Some(String::from("dc1")),
+        ..Default::default()
+    };
+    assert!(client.put(&pair, Some(&write_options)).await.unwrap().0);
+    let query_options = QueryOptions {
+        datacenter: Some(String::from("dc1")),
+        ..Default::default()
+    };
+    let (stored, _) = client.get("dctest", Some(&query_options)).await.unwrap();
+    assert_eq!(stored.unwrap().Value.unwrap(), b"value");
+
+    client.delete("dctest", Some(&write_options)).await.unwrap();
+}
+
+#[tokio::test]
+async fn kv_binary_value_test() {
+    use consul::kv::KV;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    // Every byte value, including invalid UTF-8 and NULs
+    let value: Vec<u8> = (0..=255).collect();
+    let pair = KVPair {
+        Key: String::from("kvbinary"),
+        Value: Some(value.clone()),
+        ..Default::default()
+    };
+    assert!(client.put(&pair, None).await.unwrap().0);
+
+    let stored = client.get("kvbinary", None).await.unwrap().0.unwrap();
+    assert_eq!(stored.Value, Some(value));
+    assert!(stored.value_string().unwrap().is_err());
+
+    let text = KVPair {
+        Key: String::from("kvbinary"),
+        Value: Some("héllo".as_bytes().to_vec()),
+        ..Default::default()
+    };
+    assert!(client.put(&text, None).await.unwrap().0);
+    let stored = client.get("kvbinary", None).await.unwrap().0.unwrap();
+    assert_eq!(stored.value_string().unwrap().unwrap(), "héllo");
+
+    assert!(client.delete("kvbinary", None).await.unwrap().0);
+}
+
+#[test]
+fn kv_null_value_test() {
+    let pair: KVPair = serde_json::from_str(r#"{"Key":"folder/","Value":null}"#).unwrap();
+    assert_eq!(pair.Value, None);
+    assert!(pair.value_string().is_none());
+    let pair: KVPair = serde_json::from_str(r#"{"Key":"empty","Value":""}"#).unwrap();
+    assert_eq!(pair.Value, Some(Vec::new()));
+}
+
+#[tokio::test]
+async fn kv_flags_test() {
+    use consul::kv::KV;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let pair = KVPair {
+        Key: String::from("kvflags"),
+        Value: Some(b"value".to_vec()),
+        Flags: Some(42),
+        ..Default::default()
+    };
+    assert!(client.put(&pair, None).await.unwrap().0);
+    let stored = client.get("kvflags", None).await.unwrap().0.unwrap();
+    assert_eq!(stored.Flags, Some(42));
+
+    // Only the flags change, the value stays
+    assert!(client.put_flags("kvflags", 7, None).await.unwrap().0);
+    let stored = client.get("kvflags", None).await.unwrap().0.unwrap();
+    assert_eq!(stored.Flags, Some(7));
+    assert_eq!(stored.Value.unwrap(), b"value");
+
+    // Zero is a value like any other
+    let reset = KVPair {
+        Flags: Some(0),
+        ..pair
+    };
+    assert!(client.put(&reset, None).await.unwrap().0);
+    let stored = client.get("kvflags", None).await.unwrap().0.unwrap();
+    assert_eq!(stored.Flags, Some(0));
+
+    assert!(
+        !client
+            .put_flags("kvflags-missing", 1, None)
+            .await
+            .unwrap()
+            .0
+    );
+    assert!(client.delete("kvflags", None).await.unwrap().0);
+}
+
+#[tokio::test]
+async fn kv_get_many_test() {
+    use consul::kv::KV;
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    for key in &["getmany/a", "getmany/b"] {
+        let pair = KVPair {
+            Key: String::from(*key),
+            Value: Some(key.as_bytes().to_vec()),
+            ..Default::default()
+        };
+        assert!(client.put(&pair, None).await.unwrap().0);
+    }
+
+    let pairs = client
+        .get_many(&["getmany/a", "getmany/b", "getmany/missing"], None)
+        .await;
+    assert_eq!(pairs.len(), 3);
+    for key in &["getmany/a", "getmany/b"] {
+        let pair = pairs[*key].as_ref().unwrap().as_ref().unwrap();
+        assert_eq!(pair.Value.as_deref().unwrap(), key.as_bytes());
+    }
+    assert!(pairs["getmany/missing"].as_ref().unwrap().is_none());
+
+    client.delete("getmany/a", None).await.unwrap();
+    client.delete("getmany/b", None).await.unwrap();
+}
+
+#[tokio::test]
+async fn kv_get_missing_test() {
+    use consul::errors::ErrorKind;
+    use consul::kv::KV;
+
+    const MISSING: &str =
+        "HTTP/1.1 404 Not Found\r\nX-Consul-Index: 3\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    let (address, _) = serve(vec![MISSING, FORBIDDEN, SERVER_ERROR]).await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    let (pair, meta) = client.get("missing", None).await.unwrap();
+    assert!(pair.is_none());
+    assert_eq!(meta.last_index, Some(3));
+    for status in [403, 500] {
+        match client.get("missing", None).await {
+            Err(e) => match e.kind() {
+                ErrorKind::Consul(code, _) => assert_eq!(*code, status),
+                kind => panic!("unexpected error {:?}", kind),
+            },
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn kv_put_meta_index_test() {
+    use consul::kv::{KVPair, KV};
+
+    const STORED: &str =
+        "HTTP/1.1 200 OK\r\nX-Consul-Index: 12\r\nContent-Length: 4\r\nConnection: close\r\n\r\ntrue";
+    let stored_no_index = ok("true");
+    let (address, _) = serve(vec![STORED.into(), stored_no_index]).await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    let pair = KVPair {
+        Key: String::from("indexed"),
+        ..Default::default()
+    };
+    let (_, meta) = client.put(&pair, None).await.unwrap();
+    assert_eq!(meta.last_index, Some(12));
+    let (_, meta) = client.put(&pair, None).await.unwrap();
+    assert_eq!(meta.last_index, None);
+}
+
+#[tokio::test]
+async fn kv_get_many_errors_test() {
+    use consul::kv::KV;
+
+    let found = ok("[{\"Key\":\"found\",\"Value\":\"YQ==\"}]");
+    let missing = respond("404 Not Found", "");
+    let (address, requests) = serve(vec![found, missing, SERVER_ERROR.into()]).await;
+    // One at a time, for the answers to come in the order of the keys
+    let client = ClientBuilder::new()
+        .address(&address)
+        .batch_concurrency(1)
+        .build()
+        .unwrap();
+
+    let pairs = client.get_many(&["found", "missing", "failed"], None).await;
+    assert_eq!(pairs.len(), 3);
+    let found = pairs["found"].as_ref().unwrap().as_ref().unwrap();
+    assert_eq!(found.Value.as_deref(), Some(&b"a"[..]));
+    assert!(pairs["missing"].as_ref().unwrap().is_none());
+    assert!(pairs["failed"].is_err());
+    assert_eq!(requests.count(), 3);
+}
+
+#[tokio::test]
+async fn kv_get_many_zero_concurrency_test() {
+    use consul::kv::KV;
+    use std::time::Duration;
+
+    let missing = respond("404 Not Found", "");
+    let (address, requests) = serve(vec![missing]).await;
+    let config = Config {
+        address,
+        batch_concurrency: 0,
+        ..Config::new().unwrap()
+    };
+    let client = Client::new(config);
+
+    let pairs = tokio::time::timeout(Duration::from_secs(5), client.get_many(&["a"], None))
+        .await
+        .unwrap();
+    assert!(pairs["a"].as_ref().unwrap().is_none());
+    assert_eq!(requests.count(), 1);
+}
@@ -0,0 +1,62 @@
+extern crate consul;
+use std::time::Duration;
+
+use consul::leader::LeaderElector;
+use consul::session::Session;
+use consul::{Client, Config};
+use futures::StreamExt;
+use tokio::time::timeout;
+
+const TTL: Duration = Duration::from_secs(10);
+
+#[tokio::test]
+async fn leader_election_test() {
+    let client = Client::new(Config::new().unwrap());
+
+    let first = LeaderElector::start(&client, "leadertest/election", b"first", TTL)
+        .await
+        .unwrap();
+    let mut first_changes = first.changes();
+    if first_changes.next().await == Some(false) {
+        assert_eq!(first_changes.next().await, Some(true));
+    }
+    assert!(first.is_leader());
+
+    let second = LeaderElector::start(&client, "leadertest/election", b"second", TTL)
+        .await
+        .unwrap();
+    let mut second_changes = second.changes();
+    assert_eq!(second_changes.next().await, Some(false));
+
+    first.resign().await.unwrap();
+    let next = timeout(Duration::from_secs(5), second_changes.next()).await;
+    assert_eq!(next.unwrap(), Some(true));
+    second.resign().await.unwrap();
+}
+
+#[tokio::test]
+async fn leader_session_invalidated_test() {
+    let client = Client::new(Config::new().unwrap());
+
+    let elector = LeaderElector::start(&client, "leadertest/invalidated", b"me", TTL)
+        .await
+        .unwrap();
+    let mut changes = elector.changes();
+    if changes.next().await == Some(false) {
+        assert_eq!(changes.next().await, Some(true));
+    }
+
+    // The loss is reported as soon as the lock goes, not at the next renewal
+    let session = elector.session();
+    client.destroy(&session, None).await.unwrap();
+    let next = timeout(Duration::from_secs(2), changes.next()).await;
+    assert_eq!(next.unwrap(), Some(false));
+    assert!(!elector.is_leader());
+
+    // And the campaign goes on with a new session, not at the next renewal
+    // either
+    let next = timeout(Duration::from_secs(2), changes.next()).await;
+    assert_eq!(next.unwrap(), Some(true));
+    assert_ne!(elector.session(), session);
+    elector.resign().await.unwrap();
+}
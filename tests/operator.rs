@@ -0,0 +1,242 @@
+extern crate consul;
+pub mod common;
+
+use common::{ok, respond, serve, set_up};
+use consul::operator::Operator;
+use consul::ClientBuilder;
+
+#[tokio::test]
+async fn operator_raft_configuration_test() {
+    let client = set_up();
+    let configuration = client.raft_configuration(None).await.unwrap();
+    assert!(configuration.Index > 0);
+    // The test agent is the only server
+    assert_eq!(configuration.Servers.len(), 1);
+    let server = &configuration.Servers[0];
+    assert!(server.Leader);
+    assert!(server.Voter);
+    assert!(!server.ID.is_empty());
+}
+
+#[tokio::test]
+async fn operator_raft_remove_unknown_peer_test() {
+    let client = set_up();
+    assert!(client
+        .raft_remove_peer_by_id("00000000-0000-0000-0000-000000000000", None)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn operator_keyring_test() {
+    // The gossip key configured in data/consul-test.json
+    const PRIMARY_KEY: &str = "/TDrQon9yVfHJEeyE/yxcHFS7cfYTlzwrKAqXGqQvxc=";
+    const NEW_KEY: &str = "ibHpc2MbYjFSg4AOflOwA2HCu2GTiJsIGyWQHWAAqZs=";
+    let client = set_up();
+
+    let keyrings = client.keyring_list().await.unwrap();
+    assert!(keyrings.iter().any(|k| k.WAN));
+    assert!(keyrings.iter().all(|k| k.Keys.contains_key(PRIMARY_KEY)));
+
+    client.keyring_install(NEW_KEY).await.unwrap();
+    let keyrings = client.keyring_list().await.unwrap();
+    assert!(keyrings
+        .iter()
+        .all(|k| k.Keys.get(NEW_KEY) == Some(&k.NumNodes)));
+
+    client.keyring_use(NEW_KEY).await.unwrap();
+    // The primary key cannot be removed
+    assert!(client.keyring_remove(NEW_KEY).await.is_err());
+
+    client.keyring_use(PRIMARY_KEY).await.unwrap();
+    client.keyring_remove(NEW_KEY).await.unwrap();
+    let keyrings = client.keyring_list().await.unwrap();
+    assert!(keyrings.iter().all(|k| !k.Keys.contains_key(NEW_KEY)));
+}
+
+#[tokio::test]
+async fn operator_autopilot_test() {
+    use consul::GoDuration;
+    use std::time::Duration;
+    let client = set_up();
+
+    let cfg = client.autopilot_configuration(None).await.unwrap();
+    assert!(cfg.LastContactThreshold.is_some());
+    assert!(cfg.ModifyIndex > 0);
+
+    let changed = consul::operator::AutopilotConfiguration {
+        MaxTrailingLogs: cfg.MaxTrailingLogs + 1,
+        ServerStabilizationTime: Some(Duration::from_secs(5).into()),
+        ..cfg.clone()
+    };
+    // A stale index is rejected...
+    assert!(!client
+        .autopilot_cas_configuration(&changed, cfg.ModifyIndex - 1, None)
+        .await
+        .unwrap());
+    // ...the current one is not
+    assert!(client
+        .autopilot_cas_configuration(&changed, cfg.ModifyIndex, None)
+        .await
+        .unwrap());
+    let read = client.autopilot_configuration(None).await.unwrap();
+    assert_eq!(read.MaxTrailingLogs, cfg.MaxTrailingLogs + 1);
+    assert_eq!(
+        read.ServerStabilizationTime,
+        Some(GoDuration(Duration::from_secs(5)))
+    );
+
+    client
+        .autopilot_set_configuration(&cfg, None)
+        .await
+        .unwrap();
+
+    let health = client.autopilot_health(None).await.unwrap();
+    assert_eq!(health.Servers.len(), 1);
+    assert!(health.Servers[0].Leader);
+    assert!(health.Servers[0].Voter);
+}
+
+#[tokio::test]
+async fn operator_segment_list_test() {
+    use consul::errors::ErrorKind;
+    let client = set_up();
+
+    // The test agent runs Consul OSS
+    match client.segment_list(None).await {
+        Err(e) => match e.kind() {
+            ErrorKind::EnterpriseOnly(_) => {}
+            other => panic!("unexpected error: {}", other),
+        },
+        Ok(segments) => panic!("Consul OSS listed segments: {:?}", segments),
+    }
+}
+
+#[tokio::test]
+async fn operator_area_oss_test() {
+    use consul::errors::ErrorKind;
+    use consul::operator::Area;
+    let client = set_up();
+
+    let area = Area {
+        PeerDatacenter: String::from("dc2"),
+        ..Default::default()
+    };
+    let e = client.area_create(&area, None).await.unwrap_err();
+    assert!(matches!(e.kind(), ErrorKind::EnterpriseOnly(_)));
+    let e = client.area_list(None).await.unwrap_err();
+    assert!(matches!(e.kind(), ErrorKind::EnterpriseOnly(_)));
+}
+
+#[test]
+fn operator_license_expiration_test() {
+    use consul::operator::LicenseReply;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let reply: LicenseReply = serde_json::from_str(
+        r#"{
+            "Valid": true,
+            "License": {
+                "LicenseID": "2afbf681-0d1a-0649-cb6c-1f876ea1f8e8",
+                "ExpirationTime": "2021-01-09T22:58:56.16748Z",
+                "Product": "consul",
+                "Features": ["Automated Backups", "Namespaces"]
+            },
+            "Warnings": []
+        }"#,
+    )
+    .unwrap();
+    assert!(reply.Valid);
+    assert_eq!(reply.License.Features.len(), 2);
+    let expiration = UNIX_EPOCH + Duration::new(1_610_233_136, 167_480_000);
+    assert_eq!(reply.License.expiration(), Some(expiration));
+
+    let mut license = reply.License;
+    license.ExpirationTime = String::from("2021-01-09T17:58:56.16748-05:00");
+    assert_eq!(license.expiration(), Some(expiration));
+    license.ExpirationTime = String::from("next tuesday");
+    assert_eq!(license.expiration(), None);
+}
+
+#[tokio::test]
+async fn operator_license_oss_test() {
+    use consul::errors::ErrorKind;
+    let client = set_up();
+
+    let e = client.license_get(None).await.unwrap_err();
+    assert!(matches!(e.kind(), ErrorKind::EnterpriseOnly(_)));
+}
+
+#[tokio::test]
+async fn operator_segment_list_enterprise_only_test() {
+    use consul::errors::ErrorKind;
+    use consul::operator::Operator;
+
+    let not_found = respond("404 Not Found", "");
+    let segments = ok("[\"\",\"alpha\"]");
+    let (address, _) = serve(vec![not_found, segments]).await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    let e = client.segment_list(None).await.unwrap_err();
+    assert!(matches!(e.kind(), ErrorKind::EnterpriseOnly(_)));
+    let segments = client.segment_list(None).await.unwrap();
+    assert_eq!(segments, ["", "alpha"]);
+}
+
+#[tokio::test]
+async fn operator_area_not_found_test() {
+    use consul::errors::ErrorKind;
+    use consul::operator::Operator;
+
+    // Consul Enterprise's answer for an area that does not exist
+    let not_found = respond("404 Not Found", "area not found");
+    let namespaces = respond(
+        "400 Bad Request",
+        "Namespaces are a Consul Enterprise feature",
+    );
+    let (address, _) = serve(vec![not_found, namespaces]).await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    let e = client.area_delete("unknown", None).await.unwrap_err();
+    match e.kind() {
+        ErrorKind::Consul(status, body) => {
+            assert_eq!(*status, 404);
+            assert_eq!(body, "area not found");
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+    let e = client.area_list(None).await.unwrap_err();
+    assert!(matches!(e.kind(), ErrorKind::EnterpriseOnly(_)));
+}
+
+#[tokio::test]
+async fn operator_area_join_test() {
+    use consul::operator::{Area, Operator};
+
+    let created = ok("{\"ID\":\"8f246b77-f3e1-ff88-5b48-8ec93abf3e05\"}");
+    let joined = ok("[{\"Address\":\"10.1.2.3\",\"Joined\":true,\"Error\":\"\"},{\"Address\":\"10.1.2.4\",\"Joined\":false,\"Error\":\"dial tcp: i/o timeout\"}]");
+    let (address, requests) = serve(vec![created, joined]).await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    let area = Area {
+        PeerDatacenter: String::from("dc2"),
+        RetryJoin: vec![String::from("10.1.2.3")],
+        ..Default::default()
+    };
+    let id = client.area_create(&area, None).await.unwrap();
+    assert_eq!(id, "8f246b77-f3e1-ff88-5b48-8ec93abf3e05");
+    let joins = client
+        .area_join(&id, &["10.1.2.3", "10.1.2.4"], None)
+        .await
+        .unwrap();
+    assert!(joins[0].Joined);
+    assert!(!joins[1].Joined);
+    assert_eq!(joins[1].Error, "dial tcp: i/o timeout");
+    assert_eq!(
+        requests.lines(),
+        vec![
+            "post /v1/operator/area? http/1.1",
+            "put /v1/operator/area/8f246b77-f3e1-ff88-5b48-8ec93abf3e05/join? http/1.1",
+        ]
+    );
+}
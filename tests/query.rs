@@ -0,0 +1,85 @@
+extern crate consul;
+use consul::agent::{Agent, AgentServiceRegistration};
+use consul::query::{PreparedQueryDefinition, Query, ServiceQuery};
+use consul::{Client, Config};
+
+#[tokio::test]
+async fn query_test() {
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let reg = AgentServiceRegistration {
+        ID: Some(String::from("query-test-1")),
+        Name: String::from("query-test"),
+        Port: Some(8080),
+        ..Default::default()
+    };
+    client.register_service(&reg).await.unwrap();
+
+    let query = PreparedQueryDefinition {
+        Name: String::from("query-test-lookup"),
+        Service: ServiceQuery {
+            Service: String::from("query-test"),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (id, _) = client.create(&query, None).await.unwrap();
+    assert!(!id.is_empty());
+
+    let (queries, _) = client.get(&id, None).await.unwrap();
+    assert_eq!(queries.first().unwrap().Name, "query-test-lookup");
+    let (queries, _) = client.list(None).await.unwrap();
+    assert!(queries.iter().any(|q| q.ID == id));
+
+    for query_or_name in [id.as_str(), "query-test-lookup"] {
+        let (response, _) = client.execute(query_or_name, None).await.unwrap();
+        assert_eq!(response.Service, "query-test");
+        assert_eq!(response.Datacenter, "dc1");
+        assert_eq!(response.Nodes.len(), 1);
+        assert_eq!(response.Nodes[0].Service.ID, "query-test-1");
+    }
+
+    let updated = PreparedQueryDefinition {
+        ID: id.clone(),
+        Name: String::from("query-test-lookup-renamed"),
+        ..query
+    };
+    client.update(&updated, None).await.unwrap();
+    let (queries, _) = client.get(&id, None).await.unwrap();
+    assert_eq!(queries.first().unwrap().Name, "query-test-lookup-renamed");
+
+    client.delete(&id, None).await.unwrap();
+    client.deregister_service("query-test-1").await.unwrap();
+}
+
+#[tokio::test]
+async fn query_explain_test() {
+    use consul::query::{QueryDNSOptions, QueryTemplateOptions};
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let template = PreparedQueryDefinition {
+        Name: String::from("explain-test-"),
+        Template: Some(QueryTemplateOptions {
+            Type: String::from("name_prefix_match"),
+            ..Default::default()
+        }),
+        Service: ServiceQuery {
+            Service: String::from("${name.suffix}"),
+            ..Default::default()
+        },
+        DNS: QueryDNSOptions {
+            TTL: String::from("10s"),
+        },
+        ..Default::default()
+    };
+    let (id, _) = client.create(&template, None).await.unwrap();
+
+    let (explained, _) = client.explain("explain-test-web", None).await.unwrap();
+    assert_eq!(explained.Query.ID, id);
+    assert_eq!(explained.Query.Service.Service, "web");
+    assert_eq!(explained.Query.DNS.TTL, "10s");
+
+    client.delete(&id, None).await.unwrap();
+}
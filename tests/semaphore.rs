@@ -0,0 +1,87 @@
+extern crate consul;
+pub mod common;
+use std::time::Duration;
+
+use common::{ok, respond, serve};
+use consul::semaphore::Semaphore;
+use consul::session::Session;
+use consul::{Client, Config};
+
+const TTL: Duration = Duration::from_secs(10);
+
+#[tokio::test]
+async fn semaphore_test() {
+    let client = Client::new(Config::new().unwrap());
+
+    let a = Semaphore::new(&client, "semaphoretest/limit", 2, TTL)
+        .await
+        .unwrap();
+    let b = Semaphore::new(&client, "semaphoretest/limit", 2, TTL)
+        .await
+        .unwrap();
+    let c = Semaphore::new(&client, "semaphoretest/limit", 2, TTL)
+        .await
+        .unwrap();
+    let wait = Duration::from_millis(500);
+    assert!(a.acquire(wait).await.unwrap());
+    assert!(b.acquire(wait).await.unwrap());
+    assert!(!c.acquire(wait).await.unwrap());
+
+    // A slot given back can be taken
+    a.release().await.unwrap();
+    assert!(c.acquire(wait).await.unwrap());
+
+    // Contenders must agree on the limit
+    assert!(Semaphore::new(&client, "semaphoretest/limit", 3, TTL)
+        .await
+        .unwrap()
+        .acquire(wait)
+        .await
+        .is_err());
+
+    a.close().await.unwrap();
+    b.close().await.unwrap();
+    c.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn semaphore_invalidated_holder_test() {
+    let client = Client::new(Config::new().unwrap());
+
+    let holder = Semaphore::new(&client, "semaphoretest/cleanup", 1, TTL)
+        .await
+        .unwrap();
+    let waiter = Semaphore::new(&client, "semaphoretest/cleanup", 1, TTL)
+        .await
+        .unwrap();
+    assert!(holder.acquire(Duration::from_millis(500)).await.unwrap());
+
+    // The slot of a vanished session goes to the next contender
+    client.destroy(holder.session(), None).await.unwrap();
+    assert!(waiter.acquire(Duration::from_secs(5)).await.unwrap());
+    waiter.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn semaphore_lost_session_test() {
+    use consul::errors::ErrorKind;
+    use consul::ClientBuilder;
+
+    let created = ok("{\"ID\":\"adf4238a-882b-9ddc-4a9d-5b6758e4159e\"}");
+    let acquired = ok("true");
+    let not_found = respond("404 Not Found", "");
+    let (address, requests) = serve(vec![created, acquired, not_found]).await;
+    let client = ClientBuilder::new().address(&address).build().unwrap();
+
+    // Renewed every 10ms, which Consul would not allow
+    let semaphore = Semaphore::new(&client, "semaphoretest/lost", 1, Duration::from_millis(20))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let e = semaphore.acquire(Duration::from_secs(1)).await.unwrap_err();
+    assert!(matches!(e.kind(), ErrorKind::SessionLost(..)));
+    assert_eq!(
+        requests.lines()[2],
+        "put /v1/session/renew/adf4238a-882b-9ddc-4a9d-5b6758e4159e? http/1.1"
+    );
+}
@@ -2,10 +2,11 @@ extern crate consul;
 extern crate rand;
 
 use consul::session::{Session, SessionEntry};
-use consul::{Client, Config};
+use consul::{Client, Config, GoDuration};
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use rstest::*;
+use std::time::Duration;
 
 #[rstest]
 async fn session_create_test() {
@@ -28,7 +29,7 @@ async fn session_create_test() {
         1
     );
 
-    tear_down(&client, &created_session_entry.ID.unwrap()).await;
+    tear_down(&client, &created_session_entry.ID).await;
 }
 
 #[rstest]
@@ -47,7 +48,7 @@ async fn session_destroy_test() {
         1
     );
 
-    let created_session_entry_id = created_session_entry.ID.unwrap();
+    let created_session_entry_id = created_session_entry.ID;
 
     client
         .destroy(&created_session_entry_id, None)
@@ -73,13 +74,13 @@ async fn session_info_test() {
 
     let (created_session_entry, _) = client.create(&entry, None).await.unwrap();
 
-    let created_session_entry_id = created_session_entry.ID.unwrap();
+    let created_session_entry_id = created_session_entry.ID;
 
     let (session_entries, _) = client.info(&created_session_entry_id, None).await.unwrap();
 
     assert_eq!(session_entries.len(), 1);
 
-    let session_entry = session_entries.get(0);
+    let session_entry = session_entries.first();
 
     assert_eq!(
         *session_entry.as_ref().unwrap().Name.as_ref().unwrap(),
@@ -89,6 +90,30 @@ async fn session_info_test() {
     tear_down(&client, &created_session_entry_id).await;
 }
 
+#[rstest]
+async fn session_lock_delay_test() {
+    let (client, unique_test_identifier) = set_up();
+
+    let entry = SessionEntry {
+        Name: Some(unique_test_identifier.to_string()),
+        LockDelay: Some(Duration::from_secs(30).into()),
+        ..Default::default()
+    };
+
+    let (created_session_entry, _) = client.create(&entry, None).await.unwrap();
+
+    let created_session_entry_id = created_session_entry.ID;
+
+    let (session_entries, _) = client.info(&created_session_entry_id, None).await.unwrap();
+
+    assert_eq!(
+        session_entries.first().unwrap().LockDelay,
+        Some(GoDuration(Duration::from_secs(30)))
+    );
+
+    tear_down(&client, &created_session_entry_id).await;
+}
+
 #[rstest]
 async fn session_list_test() {
     let (client, unique_test_identifier) = set_up();
@@ -109,7 +134,7 @@ async fn session_list_test() {
 
         let (created_session_entry, _) = client.create(&entry, None).await.unwrap();
 
-        session_ids.push(created_session_entry.ID.unwrap());
+        session_ids.push(created_session_entry.ID);
     }
 
     let (session_entries, _) = client.list(None).await.unwrap();
@@ -146,7 +171,7 @@ async fn session_node_test() {
 
     let (created_session_entry, _) = client.create(&entry, None).await.unwrap();
 
-    let created_session_entry_id = created_session_entry.ID.unwrap();
+    let created_session_entry_id = created_session_entry.ID;
 
     let system_hostname = hostname::get().unwrap().into_string().unwrap();
 
@@ -173,7 +198,7 @@ async fn session_renew_test() {
 
     let (created_session_entry, _) = client.create(&entry, None).await.unwrap();
 
-    let created_session_entry_id = created_session_entry.ID.unwrap();
+    let created_session_entry_id = created_session_entry.ID;
 
     client.renew(&created_session_entry_id, None).await.unwrap();
 
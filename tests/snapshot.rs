@@ -0,0 +1,67 @@
+extern crate consul;
+pub mod common;
+
+use common::{serve, set_up};
+use consul::kv::{KVPair, KV};
+use consul::snapshot::Snapshot;
+use consul::ClientBuilder;
+
+#[tokio::test]
+async fn snapshot_save_restore_test() {
+    let client = set_up();
+
+    let pair = KVPair {
+        Key: String::from("snapshottest"),
+        Value: Some(b"before".to_vec()),
+        ..Default::default()
+    };
+    client.put(&pair, None).await.unwrap();
+
+    let (data, meta) = client.save(None).await.unwrap();
+    // A gzip archive
+    assert_eq!(&data[..2], &[0x1f, 0x8b]);
+    assert!(meta.last_index.unwrap() > 0);
+
+    let changed = KVPair {
+        Value: Some(b"after".to_vec()),
+        ..pair
+    };
+    client.put(&changed, None).await.unwrap();
+
+    client.restore(&data, None).await.unwrap();
+    let (stored, _) = client.get("snapshottest", None).await.unwrap();
+    assert_eq!(stored.unwrap().Value.unwrap(), b"before");
+    client.delete("snapshottest", None).await.unwrap();
+}
+
+#[tokio::test]
+async fn snapshot_save_stream_test() {
+    use consul::{ConsistencyMode, QueryOptions};
+    use futures::StreamExt;
+
+    const SNAPSHOT: &str = "HTTP/1.1 200 OK\r\nX-Consul-Index: 7\r\nContent-Length: 13\r\nConnection: close\r\n\r\narchive-bytes";
+    let (address, requests) = serve(vec![SNAPSHOT]).await;
+    let client = ClientBuilder::new()
+        .address(&address)
+        .token("snapshot-token")
+        .gzip(true)
+        .build()
+        .unwrap();
+
+    let options = QueryOptions {
+        consistency: ConsistencyMode::Stale,
+        ..Default::default()
+    };
+    let (chunks, meta) = client.save_stream(Some(&options)).await.unwrap();
+    assert_eq!(meta.last_index, Some(7));
+    let mut data = Vec::new();
+    for chunk in chunks.collect::<Vec<_>>().await {
+        data.extend_from_slice(&chunk.unwrap());
+    }
+    assert_eq!(data, b"archive-bytes");
+    let head = &requests.heads()[0];
+    assert!(head.starts_with("get /v1/snapshot?stale= http/1.1"));
+    assert!(head.contains("x-consul-token: snapshot-token\r\n"));
+    // A snapshot is gzip data already
+    assert!(!head.contains("accept-encoding"));
+}
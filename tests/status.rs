@@ -0,0 +1,15 @@
+extern crate consul;
+use consul::status::Status;
+use consul::{Client, Config};
+
+#[tokio::test]
+async fn status_test() {
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    // The test agent is a single bootstrapped server, hence its own leader
+    let leader = client.leader().await.unwrap();
+    assert!(!leader.is_empty());
+    let peers = client.peers().await.unwrap();
+    assert_eq!(peers, vec![leader]);
+}
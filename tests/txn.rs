@@ -0,0 +1,54 @@
+extern crate consul;
+use consul::kv::KV;
+use consul::txn::{KVOp, KVTxnOp, Txn, TxnOp, TxnResult};
+use consul::{Client, Config};
+
+fn kv_op(verb: KVOp, key: &str, value: &[u8], index: u64) -> TxnOp {
+    TxnOp::KV(KVTxnOp {
+        Verb: verb,
+        Key: String::from(key),
+        Value: value.to_vec(),
+        Index: index,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn txn_test() {
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let ops = vec![
+        kv_op(KVOp::Set, "txntest/a", b"1", 0),
+        kv_op(KVOp::Set, "txntest/b", b"2", 0),
+        kv_op(KVOp::Get, "txntest/a", b"", 0),
+    ];
+    let response = client.run(ops, None).await.unwrap();
+    assert!(response.is_committed());
+    let results = response.Results.unwrap();
+    assert_eq!(results.len(), 3);
+    let TxnResult::KV(a) = &results[2];
+    assert_eq!(a.Value.as_deref(), Some(&b"1"[..]));
+    let index = a.ModifyIndex.unwrap();
+
+    // A stale index rolls the whole transaction back
+    let ops = vec![
+        kv_op(KVOp::Set, "txntest/b", b"3", 0),
+        kv_op(KVOp::Cas, "txntest/a", b"4", index - 1),
+    ];
+    let response = client.run(ops, None).await.unwrap();
+    assert!(!response.is_committed());
+    let errors = response.Errors.unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].OpIndex, 1);
+    let (b, _) = client.get("txntest/b", None).await.unwrap();
+    assert_eq!(b.unwrap().Value.unwrap(), b"2");
+
+    let ops = vec![
+        kv_op(KVOp::DeleteCas, "txntest/a", b"", index),
+        kv_op(KVOp::Delete, "txntest/b", b"", 0),
+    ];
+    assert!(client.run(ops, None).await.unwrap().is_committed());
+    let (keys, _) = client.keys("txntest/", None, None).await.unwrap();
+    assert!(keys.is_empty());
+}
@@ -0,0 +1,118 @@
+extern crate consul;
+pub mod common;
+
+use common::{serve, FORBIDDEN};
+use consul::kv::{KVPair, KV};
+use consul::watch::Watch;
+use consul::{Client, ClientBuilder, Config, RetryPolicy};
+use futures::StreamExt;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn watch_kv_test() {
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let pair = KVPair {
+        Key: String::from("watchtest"),
+        Value: Some(b"first".to_vec()),
+        ..Default::default()
+    };
+    client.put(&pair, None).await.unwrap();
+
+    let mut watch = client.watch_kv("watchtest");
+    let current = watch.next().await.unwrap().unwrap().unwrap();
+    assert_eq!(current.Value.unwrap(), b"first");
+
+    let writer = client.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let changed = KVPair {
+            Value: Some(b"second".to_vec()),
+            ..pair
+        };
+        writer.put(&changed, None).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        writer.delete("watchtest", None).await.unwrap();
+    });
+
+    let next = tokio::time::timeout(Duration::from_secs(10), watch.next())
+        .await
+        .unwrap();
+    assert_eq!(next.unwrap().unwrap().unwrap().Value.unwrap(), b"second");
+    let next = tokio::time::timeout(Duration::from_secs(10), watch.next())
+        .await
+        .unwrap();
+    assert!(next.unwrap().unwrap().is_none());
+}
+
+#[tokio::test]
+async fn watch_service_test() {
+    use consul::agent::{Agent, AgentServiceCheck, AgentServiceRegistration};
+    let config = Config::new().unwrap();
+    let client = Client::new(config);
+
+    let reg = AgentServiceRegistration {
+        ID: Some(String::from("watch-test-1")),
+        Name: String::from("watch-test"),
+        Check: Some(AgentServiceCheck {
+            TTL: Some(String::from("30s")),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    client.register_service(&reg).await.unwrap();
+
+    // The TTL check starts critical
+    let mut watch = client.watch_service("watch-test", true);
+    let instances = watch.next().await.unwrap().unwrap();
+    assert!(instances.is_empty());
+
+    client
+        .check_pass("service:watch-test-1", None)
+        .await
+        .unwrap();
+    let instances = tokio::time::timeout(Duration::from_secs(10), watch.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].Service.ID, "watch-test-1");
+
+    client.deregister_service("watch-test-1").await.unwrap();
+    let instances = tokio::time::timeout(Duration::from_secs(10), watch.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(instances.is_empty());
+}
+
+#[tokio::test]
+async fn watch_error_backoff_test() {
+    const FOUND: &str = "HTTP/1.1 200 OK\r\nX-Consul-Index: 5\r\nContent-Length: 32\r\nConnection: close\r\n\r\n[{\"Key\":\"found\",\"Value\":\"YQ==\"}]";
+    let (address, requests) = serve(vec![FORBIDDEN, FORBIDDEN, FORBIDDEN, FOUND]).await;
+    let policy = RetryPolicy {
+        max_retries: 0,
+        base_delay: Duration::from_millis(50),
+        max_delay: Duration::from_millis(150),
+    };
+    let client = ClientBuilder::new()
+        .address(&address)
+        .retry(policy)
+        .build()
+        .unwrap();
+
+    let mut watch = client.watch_kv("found");
+    assert!(watch.next().await.unwrap().is_err());
+    // Each error in a row doubles the wait before the next query, up to
+    // `max_delay`
+    for expected in [50, 100, 150] {
+        let start = Instant::now();
+        let next = watch.next().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(expected));
+        assert_eq!(next.is_ok(), expected == 150);
+    }
+    assert_eq!(requests.count(), 4);
+}